@@ -0,0 +1,91 @@
+//! Compares logging gizmos as RON strings with logging them as typed values,
+//! one event per gizmo or in a single batch.
+//!
+//! Run with `cargo bench`.
+
+use bevy::{
+    color::Hsva,
+    ecs::system::RunSystemOnce,
+    gizmos::gizmos::GizmoStorage,
+    log::debug,
+    math::{Rot2, Vec2},
+    prelude::*,
+};
+use bevy_gizmo_log::{
+    gizmo::{self, typed, GizmoBatch},
+    render_gizmo_log_events, GizmoLayer, LogGizmoConfigGroup,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+/// Number of gizmos logged between two runs of [`render_gizmo_log_events`].
+const GIZMOS_PER_FRAME: usize = 250;
+
+fn vertices(i: usize) -> impl Iterator<Item = (Vec2, Hsva)> {
+    let radius = i as f32 / GIZMOS_PER_FRAME as f32;
+    (0..7).map(move |j| {
+        let degrees = j as f32 * 60.;
+        (
+            Rot2::degrees(degrees) * Vec2::X * 250. * radius,
+            Hsva::new(degrees, radius, 1., 1.),
+        )
+    })
+}
+
+/// A headless app that only has what [`render_gizmo_log_events`] needs.
+fn app_and_layer() -> (App, GizmoLayer) {
+    let mut app = App::new();
+    app.init_resource::<Time>();
+    let layer = GizmoLayer::new(&mut app, Update);
+    (app, layer)
+}
+
+fn render_frame(app: &mut App) {
+    app.world_mut()
+        .run_system_once(render_gizmo_log_events)
+        .unwrap();
+    app.world_mut()
+        .resource_mut::<GizmoStorage<LogGizmoConfigGroup, ()>>()
+        .clear();
+}
+
+fn bench_frame(c: &mut Criterion, name: &str, log_frame: fn()) {
+    let (mut app, layer) = app_and_layer();
+    let subscriber = Registry::default().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                log_frame();
+                render_frame(&mut app);
+            });
+        });
+    });
+}
+
+fn string_path(c: &mut Criterion) {
+    bench_frame(c, "linestrip_gradient_2d string", || {
+        for i in 0..GIZMOS_PER_FRAME {
+            debug!(gizmo = gizmo::linestrip_gradient_2d(vertices(i)));
+        }
+    });
+}
+
+fn typed_path(c: &mut Criterion) {
+    bench_frame(c, "linestrip_gradient_2d typed", || {
+        for i in 0..GIZMOS_PER_FRAME {
+            debug!(gizmo = %typed::linestrip_gradient_2d(vertices(i)));
+        }
+    });
+}
+
+fn batched_path(c: &mut Criterion) {
+    bench_frame(c, "linestrip_gradient_2d batched", || {
+        let batch: GizmoBatch = (0..GIZMOS_PER_FRAME)
+            .map(|i| typed::linestrip_gradient_2d(vertices(i)))
+            .collect();
+        debug!(gizmo = %batch);
+    });
+}
+
+criterion_group!(benches, string_path, typed_path, batched_path);
+criterion_main!(benches);
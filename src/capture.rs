@@ -0,0 +1,105 @@
+use std::sync::{Arc, Mutex};
+
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::{
+    layer::Context, layer::SubscriberExt, registry::LookupSpan, Layer, Registry,
+};
+
+use crate::{frame, gizmo::GizmoCommand, visitor::extract_gizmo_command};
+
+/// A [`tracing_subscriber::Layer`] that collects gizmo logs, for use in tests.
+///
+/// Clones of a [`GizmoCapture`] share the same gizmos, so a clone can be
+/// added to a subscriber while the original is used to inspect what was logged.
+/// Usually [`with_captured_gizmos`] is more convenient.
+///
+/// [`tracing_subscriber::Layer`]: tracing_subscriber::Layer
+#[derive(Clone, Default)]
+pub struct GizmoCapture {
+    gizmos: Arc<Mutex<Vec<GizmoCommand>>>,
+}
+
+impl GizmoCapture {
+    /// Create a new [`GizmoCapture`] that hasn't captured anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The gizmos captured so far, in the order they were logged.
+    pub fn gizmos(&self) -> Vec<GizmoCommand> {
+        self.gizmos.lock().unwrap().clone()
+    }
+
+    /// Remove and return the gizmos captured so far.
+    pub fn take(&self) -> Vec<GizmoCommand> {
+        std::mem::take(&mut self.gizmos.lock().unwrap())
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for GizmoCapture {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        frame::on_new_span(attrs, id, &ctx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        frame::on_record(id, values, &ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(Ok(logged_gizmo)) = extract_gizmo_command(event) {
+            let command = frame::apply(logged_gizmo.command, event, &ctx);
+            self.gizmos.lock().unwrap().push(command);
+        }
+    }
+}
+
+/// Run `f` and return every gizmo it logged on the current thread.
+///
+/// ```
+/// # use bevy_color::{palettes::css::RED, Color};
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// use bevy_gizmo_log::{
+///     gizmo::{line, GizmoCommand},
+///     with_captured_gizmos,
+/// };
+///
+/// let gizmos = with_captured_gizmos(|| {
+///     debug!(gizmo = line(Vec3::ZERO, Vec3::X, RED));
+/// });
+/// assert_eq!(
+///     gizmos,
+///     [GizmoCommand::Line {
+///         start: Vec3::ZERO,
+///         end: Vec3::X,
+///         color: Color::from(RED),
+///     }]
+/// );
+/// ```
+pub fn with_captured_gizmos(f: impl FnOnce()) -> Vec<GizmoCommand> {
+    let capture = GizmoCapture::new();
+    let subscriber = Registry::default().with(capture.clone());
+    tracing::subscriber::with_default(subscriber, f);
+    capture.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::Vec2;
+
+    use super::*;
+    use crate::gizmo::{circle_2d, typed};
+
+    #[test]
+    fn test_capture_string_and_typed() {
+        let gizmos = with_captured_gizmos(|| {
+            tracing::debug!(gizmo = circle_2d(Vec2::ZERO, 1.0, RED));
+            tracing::debug!(gizmo = %typed::circle_2d(Vec2::ZERO, 1.0, RED));
+            tracing::debug!("not a gizmo");
+        });
+        assert_eq!(gizmos.len(), 2);
+        assert_eq!(gizmos[0], gizmos[1]);
+        assert_eq!(with_captured_gizmos(|| {}), []);
+    }
+}
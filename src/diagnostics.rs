@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use bevy_app::{App, Last, Plugin};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::system::{ResMut, Resource};
+use tracing::{callsite::Identifier, Metadata};
+
+use crate::visitor::GizmoFieldError;
+
+/// Plugin that helps find out why gizmo logs aren't drawn.
+///
+/// - Gizmo fields that can't be drawn, such as a misspelled gizmo string or
+///   a value logged with `?` that isn't a [`GizmoValue`], are reported with
+///   a warning once per callsite.
+/// - The number of gizmos parsed, dropped and rendered every frame are
+///   recorded as bevy [`Diagnostics`].
+///
+/// Also enabled by [`GizmoLogPlugin::diagnostics`].
+///
+/// ```no_run
+/// # use bevy::DefaultPlugins;
+/// # use bevy_app::{App, PluginGroup};
+/// # use bevy_diagnostic::LogDiagnosticsPlugin;
+/// # use bevy_gizmo_log::{GizmoLogDiagnosticsPlugin, GizmoLogPlugin};
+/// # use bevy_log::LogPlugin;
+/// App::new()
+///     .add_plugins(DefaultPlugins.build().disable::<LogPlugin>())
+///     .add_plugins(GizmoLogPlugin::default())
+///     .add_plugins(GizmoLogDiagnosticsPlugin)
+///     .add_plugins(LogDiagnosticsPlugin::default());
+/// ```
+///
+/// [`GizmoValue`]: crate::gizmo::GizmoValue
+/// [`Diagnostics`]: bevy_diagnostic::Diagnostics
+/// [`GizmoLogPlugin::diagnostics`]: crate::GizmoLogPlugin::diagnostics
+#[derive(Default)]
+pub struct GizmoLogDiagnosticsPlugin;
+
+impl GizmoLogDiagnosticsPlugin {
+    /// Gizmo logs that were received and decoded.
+    pub const PARSED: DiagnosticPath = DiagnosticPath::const_new("gizmo_log/parsed");
+    /// Gizmo logs that were received but couldn't be drawn, or that were
+    /// dropped because the queue of [`GizmoLayer`](crate::GizmoLayer) was full.
+    pub const DROPPED: DiagnosticPath = DiagnosticPath::const_new("gizmo_log/dropped");
+    /// Gizmos drawn, including persistent and keyed gizmos logged in earlier frames.
+    pub const RENDERED: DiagnosticPath = DiagnosticPath::const_new("gizmo_log/rendered");
+}
+
+impl Plugin for GizmoLogDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GizmoLogStats>()
+            .register_diagnostic(Diagnostic::new(Self::PARSED).with_smoothing_factor(0.0))
+            .register_diagnostic(Diagnostic::new(Self::DROPPED).with_smoothing_factor(0.0))
+            .register_diagnostic(Diagnostic::new(Self::RENDERED).with_smoothing_factor(0.0))
+            .add_systems(Last, record_gizmo_log_diagnostics);
+    }
+}
+
+/// Bevy resource that counts gizmo logs between two diagnostic measurements.
+///
+/// [`render_gizmo_log_events`](crate::render_gizmo_log_events) only counts
+/// and reports problems if this resource exists.
+#[derive(Resource, Default)]
+pub struct GizmoLogStats {
+    pub(crate) parsed: u64,
+    pub(crate) dropped: u64,
+    pub(crate) rendered: u64,
+    reported_callsites: HashSet<Identifier>,
+}
+
+impl GizmoLogStats {
+    /// Count a gizmo that can't be drawn and warn about it if it is the
+    /// first one from its callsite.
+    pub(crate) fn drop_gizmo(&mut self, err: &GizmoFieldError, metadata: &'static Metadata) {
+        self.dropped += 1;
+        if self.reported_callsites.insert(metadata.callsite()) {
+            tracing::warn!(
+                "Could not draw gizmo logged at {}:{}: {err}",
+                metadata.file().unwrap_or("<unknown>"),
+                metadata.line().unwrap_or_default(),
+            );
+        }
+    }
+}
+
+fn record_gizmo_log_diagnostics(mut stats: ResMut<GizmoLogStats>, mut diagnostics: Diagnostics) {
+    diagnostics.add_measurement(&GizmoLogDiagnosticsPlugin::PARSED, || stats.parsed as f64);
+    diagnostics.add_measurement(&GizmoLogDiagnosticsPlugin::DROPPED, || stats.dropped as f64);
+    diagnostics.add_measurement(&GizmoLogDiagnosticsPlugin::RENDERED, || {
+        stats.rendered as f64
+    });
+    stats.parsed = 0;
+    stats.dropped = 0;
+    stats.rendered = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::Update;
+    use bevy_color::palettes::css::RED;
+    use bevy_diagnostic::DiagnosticsStore;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec3;
+    use bevy_time::Time;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{gizmo::arrow, render_gizmo_log_events, GizmoLayer};
+
+    #[test]
+    fn test_counts() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(GizmoLogDiagnosticsPlugin);
+        let layer = GizmoLayer::new(&mut app, Update);
+        let subscriber = Registry::default().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(
+                gizmo = arrow(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.ttl_frames = 2
+            );
+            tracing::debug!(gizmo = "(v:1,gizmo:Arow())");
+            tracing::debug!(gizmo = ?Vec3::ONE);
+        });
+        app.world_mut()
+            .run_system_once(render_gizmo_log_events)
+            .unwrap();
+        app.world_mut()
+            .run_system_once(record_gizmo_log_diagnostics)
+            .unwrap();
+
+        let store = app.world().resource::<DiagnosticsStore>();
+        let value = |path: &DiagnosticPath| store.get(path).unwrap().value().unwrap();
+        assert_eq!(value(&GizmoLogDiagnosticsPlugin::PARSED), 1.0);
+        assert_eq!(value(&GizmoLogDiagnosticsPlugin::DROPPED), 2.0);
+        assert_eq!(value(&GizmoLogDiagnosticsPlugin::RENDERED), 1.0);
+    }
+}
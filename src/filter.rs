@@ -0,0 +1,175 @@
+use bevy_app::App;
+use bevy_ecs::system::Resource;
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::{
+    filter::{combinator::Or, FilterExt, FilterFn, ParseError},
+    reload, EnvFilter,
+};
+
+/// Bevy resource that controls which gizmo logs are drawn while the app is
+/// running, e.g. from a debug console or a key binding.
+///
+/// It filters gizmo logs with [`EnvFilter`] directives, independently from
+/// the filter of stderr. It is inserted by [`GizmoLogPlugin`], starting with
+/// [`GizmoLogPlugin::gizmo_filter`].
+///
+/// ```
+/// # use bevy_ecs::system::ResMut;
+/// # use bevy_gizmo_log::GizmoFilter;
+/// fn show_physics_gizmos(mut filter: ResMut<GizmoFilter>) {
+///     filter.set("warn,my_game::physics=trace").unwrap();
+/// }
+/// ```
+///
+/// With a custom subscriber, create the filter with [`GizmoFilter::reloadable`]
+/// and apply it to [`GizmoLayer`].
+///
+/// [`GizmoLogPlugin`]: crate::GizmoLogPlugin
+/// [`GizmoLogPlugin::gizmo_filter`]: crate::GizmoLogPlugin::gizmo_filter
+/// [`GizmoLayer`]: crate::GizmoLayer
+#[derive(Resource)]
+pub struct GizmoFilter {
+    directives: String,
+    reload: Box<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>,
+}
+
+impl GizmoFilter {
+    /// Create a filter from `directives` and insert a [`GizmoFilter`] resource
+    /// that changes it.
+    ///
+    /// The filter is meant to be applied to [`GizmoLayer`], and to
+    /// [`GizmoRecorder`] to only record gizmos that are drawn, as a
+    /// per-layer filter:
+    ///
+    /// ```
+    /// # use bevy_app::{App, PostUpdate};
+    /// # use bevy_gizmo_log::{GizmoFilter, GizmoLayer};
+    /// # use tracing_subscriber::{layer::SubscriberExt, Layer, Registry};
+    /// let mut app = App::new();
+    /// let filter = GizmoFilter::reloadable(&mut app, "[{gizmo}]=debug").unwrap();
+    /// let subscriber =
+    ///     Registry::default().with(GizmoLayer::new(&mut app, PostUpdate).with_filter(filter));
+    /// ```
+    ///
+    /// The directives only filter events. Spans pass at every level, so that
+    /// gizmos logged in a span below the filter level are still drawn relative
+    /// to its `gizmo.transform`, and the span is still shown by the inspector.
+    ///
+    /// [`GizmoLayer`]: crate::GizmoLayer
+    /// [`GizmoRecorder`]: crate::GizmoRecorder
+    pub fn reloadable<S: Subscriber>(
+        app: &mut App,
+        directives: impl Into<String>,
+    ) -> Result<ReloadableGizmoFilter<S>, ParseError> {
+        let directives = directives.into();
+        let (filter, handle) = reload::Layer::new(EnvFilter::try_new(&directives)?);
+        app.insert_resource(GizmoFilter {
+            directives,
+            reload: Box::new(move |filter| handle.reload(filter)),
+        });
+        let is_span: fn(&Metadata<'_>) -> bool = |metadata| metadata.is_span();
+        Ok(filter.or(FilterFn::new(is_span)))
+    }
+
+    /// The directives of the current filter.
+    pub fn directives(&self) -> &str {
+        &self.directives
+    }
+
+    /// Replace the filter with `directives`, such as `"warn,my_game::physics=trace"`.
+    ///
+    /// If the directives are invalid, the current filter is kept.
+    pub fn set(&mut self, directives: impl Into<String>) -> Result<(), GizmoFilterError> {
+        let directives = directives.into();
+        let filter = EnvFilter::try_new(&directives).map_err(GizmoFilterError::Parse)?;
+        (self.reload)(filter).map_err(GizmoFilterError::Reload)?;
+        self.directives = directives;
+        Ok(())
+    }
+}
+
+/// The per-layer filter created by [`GizmoFilter::reloadable`].
+pub type ReloadableGizmoFilter<S> =
+    Or<reload::Layer<EnvFilter, S>, FilterFn<fn(&Metadata<'_>) -> bool>, S>;
+
+/// Error returned by [`GizmoFilter::set`].
+#[derive(Debug)]
+pub enum GizmoFilterError {
+    Parse(ParseError),
+    /// The subscriber that the filter was applied to was dropped.
+    Reload(reload::Error),
+}
+
+impl std::fmt::Display for GizmoFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GizmoFilterError::Parse(err) => write!(f, "could not parse gizmo filter: {err}"),
+            GizmoFilterError::Reload(err) => write!(f, "could not reload gizmo filter: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GizmoFilterError {}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::Update;
+    use bevy_color::palettes::css::RED;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::{Mat4, Vec3};
+    use bevy_time::Time;
+    use tracing_subscriber::{layer::SubscriberExt, Layer, Registry};
+
+    use super::*;
+    use crate::{
+        diagnostics::GizmoLogStats,
+        gizmo::{line, transform, GizmoCommand},
+        render_gizmo_log_events, GizmoCapture, GizmoLayer, GizmoLogDiagnosticsPlugin,
+    };
+
+    #[test]
+    fn test_set_filter() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(GizmoLogDiagnosticsPlugin);
+        let filter = GizmoFilter::reloadable(&mut app, "warn").unwrap();
+        let layer = GizmoLayer::new(&mut app, Update).with_filter(filter);
+
+        let parsed = |app: &mut App| {
+            app.world_mut()
+                .run_system_once(render_gizmo_log_events)
+                .unwrap();
+            std::mem::take(&mut app.world_mut().resource_mut::<GizmoLogStats>().parsed)
+        };
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::ONE, RED));
+            tracing::warn!(gizmo = line(Vec3::ZERO, Vec3::ONE, RED));
+            assert_eq!(parsed(&mut app), 1);
+
+            let mut filter = app.world_mut().resource_mut::<GizmoFilter>();
+            assert!(filter.set("not a [filter").is_err());
+            assert_eq!(filter.directives(), "warn");
+            filter.set("debug").unwrap();
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::ONE, RED));
+            assert_eq!(parsed(&mut app), 1);
+        });
+    }
+
+    #[test]
+    fn test_spans_pass_filter() {
+        let mut app = App::new();
+        let filter = GizmoFilter::reloadable(&mut app, "warn").unwrap();
+        let capture = GizmoCapture::new();
+        let subscriber = Registry::default().with(capture.clone().with_filter(filter));
+        tracing::subscriber::with_default(subscriber, || {
+            let translation = Mat4::from_translation(Vec3::X);
+            let _span =
+                tracing::debug_span!("frame", gizmo.transform = transform(translation)).entered();
+            tracing::warn!(gizmo = line(Vec3::ZERO, Vec3::ONE, RED));
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::ONE, RED));
+        });
+        let gizmos = capture.take();
+        assert_eq!(gizmos.len(), 1);
+        assert!(matches!(gizmos[0], GizmoCommand::Transformed { .. }));
+    }
+}
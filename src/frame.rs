@@ -0,0 +1,138 @@
+use std::fmt;
+
+use bevy_math::Mat4;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+use crate::gizmo::GizmoCommand;
+
+/// The local coordinate frame set by the `gizmo.transform` field of a span.
+struct SpanTransform(Mat4);
+
+/// Visitor that reads the `gizmo.transform` field of a span.
+#[derive(Default)]
+struct TransformVisitor(Option<Mat4>);
+
+impl Visit for TransformVisitor {
+    /// Read a transform logged with `%`, which is formatted like a string.
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "gizmo.transform" {
+            self.record_str(field, &format!("{value:?}"));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "gizmo.transform" {
+            self.0 = ron::de::from_str(value).ok();
+        }
+    }
+}
+
+/// Remember the `gizmo.transform` field of a new span.
+pub(crate) fn on_new_span<S>(attrs: &span::Attributes<'_>, id: &span::Id, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let mut visitor = TransformVisitor::default();
+    attrs.record(&mut visitor);
+    store(visitor, id, ctx);
+}
+
+/// Remember the `gizmo.transform` field of a span if it is recorded after the span was created.
+pub(crate) fn on_record<S>(id: &span::Id, values: &span::Record<'_>, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let mut visitor = TransformVisitor::default();
+    values.record(&mut visitor);
+    store(visitor, id, ctx);
+}
+
+fn store<S>(visitor: TransformVisitor, id: &span::Id, ctx: &Context<'_, S>)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if let (Some(transform), Some(span)) = (visitor.0, ctx.span(id)) {
+        // Every layer that handles gizmos stores the same transform.
+        span.extensions_mut().replace(SpanTransform(transform));
+    }
+}
+
+/// Put `command` in the coordinate frame of the spans enclosing `event`.
+///
+/// Clearing keyed gizmos isn't affected by coordinate frames.
+pub(crate) fn apply<S>(
+    command: GizmoCommand,
+    event: &Event<'_>,
+    ctx: &Context<'_, S>,
+) -> GizmoCommand
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    if matches!(command, GizmoCommand::Clear { .. }) {
+        return command;
+    }
+    let Some(scope) = ctx.event_scope(event) else {
+        return command;
+    };
+    let transform = scope
+        .from_root()
+        .filter_map(|span| span.extensions().get::<SpanTransform>().map(|t| t.0))
+        .reduce(|parent, child| parent * child);
+    match transform {
+        Some(transform) => GizmoCommand::Transformed {
+            transform,
+            gizmo: Box::new(command),
+        },
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::Vec3;
+
+    use super::*;
+    use crate::{
+        gizmo::{line, transform},
+        with_captured_gizmos,
+    };
+
+    #[test]
+    fn test_nested_frames() {
+        let outer = Mat4::from_translation(Vec3::X);
+        let inner = Mat4::from_rotation_z(1.0);
+        let gizmos = with_captured_gizmos(|| {
+            let _outer = tracing::info_span!("outer", gizmo.transform = transform(outer)).entered();
+            let _inner =
+                tracing::info_span!("inner", gizmo.transform = %transform(inner)).entered();
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::X, RED));
+        });
+        let [GizmoCommand::Transformed { transform, gizmo }] = &gizmos[..] else {
+            panic!("expected a transformed gizmo, got {gizmos:?}");
+        };
+        assert!(transform.abs_diff_eq(outer * inner, 1e-6));
+        assert!(matches!(**gizmo, GizmoCommand::Line { .. }));
+
+        assert!(with_captured_gizmos(|| {
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::X, RED));
+        })
+        .iter()
+        .all(|gizmo| matches!(gizmo, GizmoCommand::Line { .. })));
+    }
+
+    #[test]
+    fn test_ignore_debug() {
+        // Only the format written by `transform` is read, not `Mat4`'s debug output.
+        let gizmos = with_captured_gizmos(|| {
+            let mat = Mat4::from_rotation_y(0.5);
+            let _span = tracing::info_span!("frame", gizmo.transform = ?mat).entered();
+            tracing::debug!(gizmo = line(Vec3::ZERO, Vec3::X, RED));
+        });
+        assert!(matches!(gizmos[..], [GizmoCommand::Line { .. }]));
+    }
+}
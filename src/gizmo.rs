@@ -2,212 +2,448 @@
 
 use bevy_color::Color;
 #[cfg(feature = "bevy")]
-use bevy_gizmos::gizmos::Gizmos;
+use bevy_gizmos::{config::GizmoConfigGroup, gizmos::Gizmos};
 use bevy_math::{Isometry2d, Isometry3d, Mat4, UVec2, UVec3, Vec2, Vec3};
 
+#[cfg(feature = "bevy")]
+use crate::tessellate::Lines;
 pub use crate::transform::IntoMat4;
+pub use batch::GizmoBatch;
+pub use primitives::{Shape2d, Shape3d};
+pub use typed::GizmoValue;
+
+mod batch;
+pub(crate) mod primitives;
+pub mod typed;
+pub mod wire;
+
+/// Key of a retained gizmo.
+///
+/// Logging a gizmo with a `gizmo.key` field keeps drawing it every frame
+/// until another gizmo is logged with the same key or the key is [`clear`]ed.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::gizmo::{clear, sphere};
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// debug!(gizmo = sphere(Vec3::ZERO, 1.0, RED), gizmo.key = "target");
+/// debug!(gizmo = clear("target"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum GizmoKey {
+    Str(String),
+    Num(u64),
+}
+
+impl From<&str> for GizmoKey {
+    fn from(key: &str) -> Self {
+        GizmoKey::Str(key.to_owned())
+    }
+}
+
+impl From<String> for GizmoKey {
+    fn from(key: String) -> Self {
+        GizmoKey::Str(key)
+    }
+}
+
+impl From<u64> for GizmoKey {
+    fn from(key: u64) -> Self {
+        GizmoKey::Num(key)
+    }
+}
+
+/// Whether an optional field of a [`GizmoCommand`] can be left out of its encoding.
+pub(crate) fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// How long a persistent gizmo should keep being drawn.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum GizmoLifetime {
+    /// Drawn until this many seconds have passed.
+    Seconds(f32),
+    /// Drawn for this many frames.
+    Frames(u32),
+}
+
+/// The coordinate space a gizmo is logged in, set by its `gizmo.space` field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) enum GizmoSpace {
+    /// World coordinates.
+    #[default]
+    World,
+    /// `"viewport"`: normalized viewport coordinates of the active camera,
+    /// from (0, 0) at the top left to (1, 1) at the bottom right.
+    Viewport,
+    /// `"pixels"`: logical pixels of the viewport of the active camera,
+    /// from (0, 0) at the top left.
+    Pixels,
+}
+
+impl GizmoSpace {
+    /// Parse the value of a `gizmo.space` field.
+    pub(crate) fn parse(space: &str) -> Option<Self> {
+        match space {
+            "world" => Some(Self::World),
+            "viewport" => Some(Self::Viewport),
+            "pixels" => Some(Self::Pixels),
+            _ => None,
+        }
+    }
+}
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub(crate) enum GizmoCommand {
+/// A gizmo, as logged by the functions in this module.
+///
+/// Logs can be captured and compared against a [`GizmoCommand`]
+/// with [`with_captured_gizmos`](crate::with_captured_gizmos).
+///
+/// New variants are added as new gizmos are supported, so matches on this
+/// enum need a wildcard arm.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub enum GizmoCommand {
+    /// Logged by [`arc_2d`].
     Arc2d {
         isometry: Isometry2d,
         arc_angle: f32,
         radius: f32,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`arc_3d`].
     Arc3d {
         angle: f32,
         radius: f32,
         isometry: Isometry3d,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`arrow`].
     Arrow {
         start: Vec3,
         end: Vec3,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        tip_length: Option<f32>,
+        #[serde(default, skip_serializing_if = "is_default")]
+        double_ended: bool,
     },
+    /// Logged by [`arrow_2d`].
     Arrow2d {
         start: Vec2,
         end: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        tip_length: Option<f32>,
+        #[serde(default, skip_serializing_if = "is_default")]
+        double_ended: bool,
     },
-    Axes {
-        transform: Mat4,
-        base_length: f32,
-    },
-    Axes2d {
-        transform: Mat4,
-        base_length: f32,
-    },
+    /// Logged by [`axes`].
+    Axes { transform: Mat4, base_length: f32 },
+    /// Logged by [`axes_2d`].
+    Axes2d { transform: Mat4, base_length: f32 },
+    /// Logged by [`GizmoBatch`]: many gizmos logged as one event.
+    Batch { gizmos: Vec<GizmoCommand> },
+    /// Logged by [`clear`] and [`clear_all`]. Clears every retained gizmo if `key` is `None`.
+    Clear { key: Option<GizmoKey> },
+    /// Logged by [`circle`].
     Circle {
         isometry: Isometry3d,
         radius: f32,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`circle_2d`].
     Circle2d {
         position: Vec2,
         radius: f32,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
-    Cuboid {
-        transform: Mat4,
+    /// Logged by [`cubic_bezier`].
+    CubicBezier {
+        control_points: Vec<[Vec3; 4]>,
         color: Color,
     },
+    /// Logged by [`cubic_bezier_2d`].
+    CubicBezier2d {
+        control_points: Vec<[Vec2; 4]>,
+        color: Color,
+    },
+    /// Logged by [`cuboid`].
+    Cuboid { transform: Mat4, color: Color },
+    /// Logged by [`ellipse`].
     Ellipse {
         isometry: Isometry3d,
         half_size: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`ellipse_2d`].
     Ellipse2d {
         isometry: Isometry2d,
         half_size: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`grid`].
     Grid {
         isometry: Isometry3d,
         cell_count: UVec2,
         spacing: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        skew: Vec2,
+        #[serde(default, skip_serializing_if = "is_default")]
+        outer_edges: [bool; 2],
     },
+    /// Logged by [`grid_2d`].
     Grid2d {
         isometry: Isometry2d,
         cell_count: UVec2,
         spacing: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        skew: Vec2,
+        #[serde(default, skip_serializing_if = "is_default")]
+        outer_edges: [bool; 2],
     },
+    /// Logged by [`grid_3d`].
     Grid3d {
         isometry: Isometry3d,
         cell_count: UVec3,
         spacing: Vec3,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        skew: Vec3,
+        #[serde(default, skip_serializing_if = "is_default")]
+        outer_edges: [bool; 3],
+    },
+    /// Logged by [`label`]. Drawn as text by [`GizmoLayer`] with the `labels`
+    /// feature, and ignored by [`GizmoCommand::draw`].
+    ///
+    /// [`GizmoLayer`]: crate::GizmoLayer
+    Label {
+        position: Vec3,
+        text: String,
+        color: Color,
     },
+    /// Logged by [`line`].
     Line {
         start: Vec3,
         end: Vec3,
         color: Color,
     },
+    /// Logged by [`line_2d`].
     Line2d {
         start: Vec2,
         end: Vec2,
         color: Color,
     },
+    /// Logged by [`line_gradient`].
     LineGradient {
         start: Vec3,
         end: Vec3,
         start_color: Color,
         end_color: Color,
     },
+    /// Logged by [`line_gradient_2d`].
     LineGradient2d {
         start: Vec2,
         end: Vec2,
         start_color: Color,
         end_color: Color,
     },
-    Linestrip {
-        positions: Vec<Vec3>,
-        color: Color,
-    },
-    Linestrip2d {
-        positions: Vec<Vec2>,
-        color: Color,
-    },
-    LinestripGradient {
-        points: Vec<(Vec3, Color)>,
-    },
-    LinestripGradient2d {
-        positions: Vec<(Vec2, Color)>,
-    },
+    /// Logged by [`linestrip`].
+    Linestrip { positions: Vec<Vec3>, color: Color },
+    /// Logged by [`linestrip_2d`].
+    Linestrip2d { positions: Vec<Vec2>, color: Color },
+    /// Logged by [`linestrip_gradient`].
+    LinestripGradient { points: Vec<(Vec3, Color)> },
+    /// Logged by [`linestrip_gradient_2d`].
+    LinestripGradient2d { positions: Vec<(Vec2, Color)> },
+    /// Logged by [`long_arc_3d_between`].
     LongArc3dBetween {
         center: Vec3,
         from: Vec3,
         to: Vec3,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
+    },
+    /// Logged by [`primitive_2d`].
+    Primitive2d {
+        primitive: Shape2d,
+        isometry: Isometry2d,
+        color: Color,
+    },
+    /// Logged by [`primitive_3d`].
+    Primitive3d {
+        primitive: Shape3d,
+        isometry: Isometry3d,
+        color: Color,
     },
+    /// Logged by [`ray`].
     Ray {
         start: Vec3,
         vector: Vec3,
         color: Color,
     },
+    /// Logged by [`ray_2d`].
     Ray2d {
         start: Vec2,
         vector: Vec2,
         color: Color,
     },
+    /// Logged by [`ray_gradient`].
     RayGradient {
         start: Vec3,
         vector: Vec3,
         start_color: Color,
         end_color: Color,
     },
+    /// Logged by [`ray_gradient_2d`].
     RayGradient2d {
         start: Vec2,
         vector: Vec2,
         start_color: Color,
         end_color: Color,
     },
+    /// Logged by [`rect`].
     Rect {
         isometry: Isometry3d,
         size: Vec2,
         color: Color,
     },
+    /// Logged by [`rect_2d`].
     Rect2d {
         isometry: Isometry2d,
         size: Vec2,
         color: Color,
     },
+    /// Logged by [`rounded_cuboid`].
     RoundedCuboid {
         isometry: Isometry3d,
         size: Vec3,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        edge_radius: Option<f32>,
+        #[serde(default, skip_serializing_if = "is_default")]
+        arc_resolution: Option<u32>,
     },
+    /// Logged by [`rounded_rect`].
     RoundedRect {
         isometry: Isometry3d,
         size: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        corner_radius: Option<f32>,
+        #[serde(default, skip_serializing_if = "is_default")]
+        arc_resolution: Option<u32>,
     },
+    /// Logged by [`rounded_rect_2d`].
     RoundedRect2d {
         isometry: Isometry2d,
         size: Vec2,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        corner_radius: Option<f32>,
+        #[serde(default, skip_serializing_if = "is_default")]
+        arc_resolution: Option<u32>,
     },
+    /// Logged by [`short_arc_3d_between`].
     ShortArc3dBetween {
         center: Vec3,
         from: Vec3,
         to: Vec3,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
     },
+    /// Logged by [`sphere`].
     Sphere {
         isometry: Isometry3d,
         radius: f32,
         color: Color,
+        #[serde(default, skip_serializing_if = "is_default")]
+        resolution: Option<u32>,
+    },
+    /// A gizmo logged inside spans with a `gizmo.transform` field, in the
+    /// local coordinate frame of the spans. See [`transform`].
+    ///
+    /// Drawn as line segments, so that any transform can be applied.
+    Transformed {
+        transform: Mat4,
+        gizmo: Box<GizmoCommand>,
     },
 }
 
 #[cfg(feature = "bevy")]
 impl GizmoCommand {
-    pub fn draw(self, gizmos: &mut Gizmos) {
-        match self {
+    /// Draw the gizmo for one frame, in the config group of `gizmos`.
+    pub fn draw<Config: GizmoConfigGroup>(&self, gizmos: &mut Gizmos<'_, '_, Config>) {
+        match *self {
             Self::Arc2d {
                 isometry,
                 arc_angle,
                 radius,
                 color,
+                resolution,
             } => {
-                gizmos.arc_2d(isometry, arc_angle, radius, color);
+                let builder = gizmos.arc_2d(isometry, arc_angle, radius, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
             Self::Arc3d {
                 angle,
                 radius,
                 isometry,
                 color,
+                resolution,
             } => {
-                gizmos.arc_3d(angle, radius, isometry, color);
+                let builder = gizmos.arc_3d(angle, radius, isometry, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
-            Self::Arrow { start, end, color } => {
-                gizmos.arrow(start, end, color);
+            Self::Arrow {
+                start,
+                end,
+                color,
+                tip_length,
+                double_ended,
+            } => {
+                let mut arrow = gizmos.arrow(start, end, color);
+                if let Some(tip_length) = tip_length {
+                    arrow = arrow.with_tip_length(tip_length);
+                }
+                if double_ended {
+                    arrow.with_double_end();
+                }
             }
-            Self::Arrow2d { start, end, color } => {
-                gizmos.arrow_2d(start, end, color);
+            Self::Arrow2d {
+                start,
+                end,
+                color,
+                tip_length,
+                double_ended,
+            } => {
+                let mut arrow = gizmos.arrow_2d(start, end, color);
+                if let Some(tip_length) = tip_length {
+                    arrow = arrow.with_tip_length(tip_length);
+                }
+                if double_ended {
+                    arrow.with_double_end();
+                }
             }
             Self::Axes {
                 transform,
@@ -221,19 +457,47 @@ impl GizmoCommand {
             } => {
                 gizmos.axes_2d(transform, base_length);
             }
+            Self::Batch { gizmos: ref batch } => {
+                for gizmo in batch {
+                    gizmo.draw(gizmos);
+                }
+            }
+            Self::Clear { .. } => {
+                // Clearing is handled by RetainedGizmos, there is nothing to draw.
+            }
             Self::Circle {
                 isometry,
                 radius,
                 color,
+                resolution,
             } => {
-                gizmos.circle(isometry, radius, color);
+                let builder = gizmos.circle(isometry, radius, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
             Self::Circle2d {
                 position,
                 radius,
                 color,
+                resolution,
+            } => {
+                let builder = gizmos.circle_2d(position, radius, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
+            }
+            Self::CubicBezier {
+                ref control_points,
+                color,
+            } => {
+                gizmos.linestrip(primitives::cubic_bezier_positions(control_points), color);
+            }
+            Self::CubicBezier2d {
+                ref control_points,
+                color,
             } => {
-                gizmos.circle_2d(position, radius, color);
+                gizmos.linestrip_2d(primitives::cubic_bezier_positions(control_points), color);
             }
             Self::Cuboid { transform, color } => {
                 gizmos.cuboid(transform, color);
@@ -242,40 +506,81 @@ impl GizmoCommand {
                 isometry,
                 half_size,
                 color,
+                resolution,
             } => {
-                gizmos.ellipse(isometry, half_size, color);
+                let builder = gizmos.ellipse(isometry, half_size, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
             Self::Ellipse2d {
                 isometry,
                 half_size,
                 color,
+                resolution,
             } => {
-                gizmos.ellipse_2d(isometry, half_size, color);
+                let builder = gizmos.ellipse_2d(isometry, half_size, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
             Self::Grid {
                 isometry,
                 cell_count,
                 spacing,
                 color,
+                skew,
+                outer_edges,
             } => {
-                gizmos.grid(isometry, cell_count, spacing, color);
+                let mut grid = gizmos.grid(isometry, cell_count, spacing, color).skew(skew);
+                if outer_edges[0] {
+                    grid = grid.outer_edges_x();
+                }
+                if outer_edges[1] {
+                    grid.outer_edges_y();
+                }
             }
             Self::Grid2d {
                 isometry,
                 cell_count,
                 spacing,
                 color,
+                skew,
+                outer_edges,
             } => {
-                gizmos.grid_2d(isometry, cell_count, spacing, color);
+                let mut grid = gizmos
+                    .grid_2d(isometry, cell_count, spacing, color)
+                    .skew(skew);
+                if outer_edges[0] {
+                    grid = grid.outer_edges_x();
+                }
+                if outer_edges[1] {
+                    grid.outer_edges_y();
+                }
             }
             Self::Grid3d {
                 isometry,
                 cell_count,
                 spacing,
                 color,
+                skew,
+                outer_edges,
             } => {
-                gizmos.grid_3d(isometry, cell_count, spacing, color);
+                let mut grid = gizmos
+                    .grid_3d(isometry, cell_count, spacing, color)
+                    .skew(skew);
+                if outer_edges[0] {
+                    grid = grid.outer_edges_x();
+                }
+                if outer_edges[1] {
+                    grid = grid.outer_edges_y();
+                }
+                if outer_edges[2] {
+                    grid.outer_edges_z();
+                }
             }
+            // Gizmos can't draw text, labels are drawn by the `labels` module.
+            Self::Label { .. } => {}
             Self::Line { start, end, color } => {
                 gizmos.line(start, end, color);
             }
@@ -298,25 +603,49 @@ impl GizmoCommand {
             } => {
                 gizmos.line_gradient_2d(start, end, start_color, end_color);
             }
-            Self::Linestrip { positions, color } => {
-                gizmos.linestrip(positions, color);
+            Self::Linestrip {
+                ref positions,
+                color,
+            } => {
+                gizmos.linestrip(positions.iter().copied(), color);
             }
-            Self::Linestrip2d { positions, color } => {
-                gizmos.linestrip_2d(positions, color);
+            Self::Linestrip2d {
+                ref positions,
+                color,
+            } => {
+                gizmos.linestrip_2d(positions.iter().copied(), color);
             }
-            Self::LinestripGradient { points } => {
-                gizmos.linestrip_gradient(points);
+            Self::LinestripGradient { ref points } => {
+                gizmos.linestrip_gradient(points.iter().copied());
             }
-            Self::LinestripGradient2d { positions } => {
-                gizmos.linestrip_gradient_2d(positions);
+            Self::LinestripGradient2d { ref positions } => {
+                gizmos.linestrip_gradient_2d(positions.iter().copied());
             }
             Self::LongArc3dBetween {
                 center,
                 from,
                 to,
                 color,
+                resolution,
+            } => {
+                let builder = gizmos.long_arc_3d_between(center, from, to, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
+            }
+            Self::Primitive2d {
+                ref primitive,
+                isometry,
+                color,
+            } => {
+                primitive.draw(gizmos, isometry, color);
+            }
+            Self::Primitive3d {
+                ref primitive,
+                isometry,
+                color,
             } => {
-                gizmos.long_arc_3d_between(center, from, to, color);
+                primitive.draw(gizmos, isometry, color);
             }
             Self::Ray {
                 start,
@@ -366,38 +695,162 @@ impl GizmoCommand {
                 isometry,
                 size,
                 color,
+                edge_radius,
+                arc_resolution,
             } => {
-                gizmos.rounded_cuboid(isometry, size, color);
+                let mut rounded = gizmos.rounded_cuboid(isometry, size, color);
+                if let Some(edge_radius) = edge_radius {
+                    rounded = rounded.edge_radius(edge_radius);
+                }
+                if let Some(arc_resolution) = arc_resolution {
+                    rounded.arc_resolution(arc_resolution);
+                }
             }
             Self::RoundedRect {
                 isometry,
                 size,
                 color,
+                corner_radius,
+                arc_resolution,
             } => {
-                gizmos.rounded_rect(isometry, size, color);
+                let mut rounded = gizmos.rounded_rect(isometry, size, color);
+                if let Some(corner_radius) = corner_radius {
+                    rounded = rounded.corner_radius(corner_radius);
+                }
+                if let Some(arc_resolution) = arc_resolution {
+                    rounded.arc_resolution(arc_resolution);
+                }
             }
             Self::RoundedRect2d {
                 isometry,
                 size,
                 color,
+                corner_radius,
+                arc_resolution,
             } => {
-                gizmos.rounded_rect_2d(isometry, size, color);
+                let mut rounded = gizmos.rounded_rect_2d(isometry, size, color);
+                if let Some(corner_radius) = corner_radius {
+                    rounded = rounded.corner_radius(corner_radius);
+                }
+                if let Some(arc_resolution) = arc_resolution {
+                    rounded.arc_resolution(arc_resolution);
+                }
             }
             Self::ShortArc3dBetween {
                 center,
                 from,
                 to,
                 color,
+                resolution,
             } => {
-                gizmos.short_arc_3d_between(center, from, to, color);
+                let builder = gizmos.short_arc_3d_between(center, from, to, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
             }
             Self::Sphere {
                 isometry,
                 radius,
                 color,
+                resolution,
+            } => {
+                let builder = gizmos.sphere(isometry, radius, color);
+                if let Some(resolution) = resolution {
+                    builder.resolution(resolution);
+                }
+            }
+            Self::Transformed { .. } => {
+                let mut lines = Lines::default();
+                lines.add(self);
+                for segment in lines.segments {
+                    gizmos.line_gradient(
+                        segment.start,
+                        segment.end,
+                        segment.start_color,
+                        segment.end_color,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Replace every color of the gizmo with `f(color)`.
+    ///
+    /// Axes are drawn with fixed colors, which are left unchanged.
+    pub(crate) fn map_colors(&mut self, mut f: impl FnMut(Color) -> Color) {
+        match self {
+            Self::Arc2d { color, .. }
+            | Self::Arc3d { color, .. }
+            | Self::Arrow { color, .. }
+            | Self::Arrow2d { color, .. }
+            | Self::Circle { color, .. }
+            | Self::Circle2d { color, .. }
+            | Self::CubicBezier { color, .. }
+            | Self::CubicBezier2d { color, .. }
+            | Self::Cuboid { color, .. }
+            | Self::Ellipse { color, .. }
+            | Self::Ellipse2d { color, .. }
+            | Self::Grid { color, .. }
+            | Self::Grid2d { color, .. }
+            | Self::Grid3d { color, .. }
+            | Self::Label { color, .. }
+            | Self::Line { color, .. }
+            | Self::Line2d { color, .. }
+            | Self::Linestrip { color, .. }
+            | Self::Linestrip2d { color, .. }
+            | Self::LongArc3dBetween { color, .. }
+            | Self::Primitive2d { color, .. }
+            | Self::Primitive3d { color, .. }
+            | Self::Ray { color, .. }
+            | Self::Ray2d { color, .. }
+            | Self::Rect { color, .. }
+            | Self::Rect2d { color, .. }
+            | Self::RoundedCuboid { color, .. }
+            | Self::RoundedRect { color, .. }
+            | Self::RoundedRect2d { color, .. }
+            | Self::ShortArc3dBetween { color, .. }
+            | Self::Sphere { color, .. } => *color = f(*color),
+            Self::LineGradient {
+                start_color,
+                end_color,
+                ..
+            }
+            | Self::LineGradient2d {
+                start_color,
+                end_color,
+                ..
+            }
+            | Self::RayGradient {
+                start_color,
+                end_color,
+                ..
+            }
+            | Self::RayGradient2d {
+                start_color,
+                end_color,
+                ..
             } => {
-                gizmos.sphere(isometry, radius, color);
+                *start_color = f(*start_color);
+                *end_color = f(*end_color);
+            }
+            Self::LinestripGradient { points } => {
+                for (_, color) in points {
+                    *color = f(*color);
+                }
             }
+            Self::LinestripGradient2d { positions } => {
+                for (_, color) in positions {
+                    *color = f(*color);
+                }
+            }
+            Self::Transformed { gizmo, .. } => gizmo.map_colors(f),
+            Self::Batch { gizmos } => {
+                for gizmo in gizmos {
+                    // A trait object, since `&mut f` would instantiate this function recursively.
+                    gizmo.map_colors(&mut f as &mut dyn FnMut(Color) -> Color);
+                }
+            }
+            Self::Axes { .. } | Self::Axes2d { .. } | Self::Clear { .. } => {}
         }
     }
 }
@@ -409,13 +862,7 @@ pub fn arc_2d(
     radius: f32,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Arc2d {
-        isometry: isometry.into(),
-        arc_angle,
-        radius,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::arc_2d(isometry, arc_angle, radius, color).into()
 }
 
 /// Gizmo log version of [`arc_3d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.arc_3d).
@@ -425,80 +872,68 @@ pub fn arc_3d(
     isometry: impl Into<Isometry3d>,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Arc3d {
-        angle,
-        radius,
-        isometry: isometry.into(),
-        color: color.into(),
-    })
-    .unwrap()
+    typed::arc_3d(angle, radius, isometry, color).into()
 }
 
 /// Gizmo log version of [`arrow`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.arrow).
 pub fn arrow(start: Vec3, end: Vec3, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Arrow {
-        start,
-        end,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::arrow(start, end, color).into()
 }
 
 /// Gizmo log version of [`arrow_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.arrow_2d).
 pub fn arrow_2d(start: Vec2, end: Vec2, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Arrow2d {
-        start,
-        end,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::arrow_2d(start, end, color).into()
 }
 
 /// Gizmo log version of [`axes`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.axes).
 pub fn axes(transform: impl IntoMat4, base_length: f32) -> String {
-    ron::ser::to_string(&GizmoCommand::Axes {
-        transform: transform.into_mat4(),
-        base_length,
-    })
-    .unwrap()
+    typed::axes(transform, base_length).into()
 }
 
 /// Gizmo log version of [`axes_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.axes_2d).
 pub fn axes_2d(transform: impl IntoMat4, base_length: f32) -> String {
-    ron::ser::to_string(&GizmoCommand::Axes2d {
-        transform: transform.into_mat4(),
-        base_length,
-    })
-    .unwrap()
+    typed::axes_2d(transform, base_length).into()
 }
 
 /// Gizmo log version of [`circle`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.circle).
 pub fn circle(isometry: impl Into<Isometry3d>, radius: f32, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Circle {
-        isometry: isometry.into(),
-        radius,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::circle(isometry, radius, color).into()
 }
 
 /// Gizmo log version of [`circle_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.circle_2d).
 pub fn circle_2d(position: Vec2, radius: f32, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Circle2d {
-        position,
-        radius,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::circle_2d(position, radius, color).into()
+}
+
+/// Stop drawing the retained gizmo logged with a `gizmo.key` field equal to `key`.
+pub fn clear(key: impl Into<GizmoKey>) -> String {
+    typed::clear(key).into()
+}
+
+/// Stop drawing every retained gizmo, both keyed and persistent.
+pub fn clear_all() -> String {
+    typed::clear_all().into()
+}
+
+/// Log a chain of cubic Bézier curves, each with 4 control points.
+///
+/// Bevy has no gizmo for Bézier curves, so this is drawn as a [`linestrip`]
+/// through points sampled along the curves.
+pub fn cubic_bezier(control_points: impl Into<Vec<[Vec3; 4]>>, color: impl Into<Color>) -> String {
+    typed::cubic_bezier(control_points, color).into()
+}
+
+/// 2D version of [`cubic_bezier`].
+pub fn cubic_bezier_2d(
+    control_points: impl Into<Vec<[Vec2; 4]>>,
+    color: impl Into<Color>,
+) -> String {
+    typed::cubic_bezier_2d(control_points, color).into()
 }
 
 /// Gizmo log version of [`cuboid`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.cuboid).
 pub fn cuboid(transform: impl IntoMat4, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Cuboid {
-        transform: transform.into_mat4(),
-        color: color.into(),
-    })
-    .unwrap()
+    typed::cuboid(transform, color).into()
 }
 
 /// Gizmo log version of [`ellipse`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ellipse).
@@ -507,12 +942,7 @@ pub fn ellipse(
     half_size: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Ellipse {
-        isometry: isometry.into(),
-        half_size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::ellipse(isometry, half_size, color).into()
 }
 
 /// Gizmo log version of [`ellipse_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ellipse_2d).
@@ -521,12 +951,7 @@ pub fn ellipse_2d(
     half_size: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Ellipse2d {
-        isometry: isometry.into(),
-        half_size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::ellipse_2d(isometry, half_size, color).into()
 }
 
 /// Gizmo log version of [`grid`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.grid).
@@ -536,13 +961,7 @@ pub fn grid(
     spacing: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Grid {
-        isometry: isometry.into(),
-        cell_count,
-        spacing,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::grid(isometry, cell_count, spacing, color).into()
 }
 
 /// Gizmo log version of [`grid_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.grid_2d).
@@ -552,13 +971,7 @@ pub fn grid_2d(
     spacing: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Grid2d {
-        isometry: isometry.into(),
-        cell_count,
-        spacing,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::grid_2d(isometry, cell_count, spacing, color).into()
 }
 
 /// Gizmo log version of [`grid_3d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.grid_3d).
@@ -568,33 +981,38 @@ pub fn grid_3d(
     spacing: Vec3,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::Grid3d {
-        isometry: isometry.into(),
-        cell_count,
-        spacing,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::grid_3d(isometry, cell_count, spacing, color).into()
+}
+
+/// Log text drawn at `position`, facing the screen.
+///
+/// ```
+/// # use bevy_color::palettes::css::WHITE;
+/// # use bevy_gizmo_log::gizmo::label;
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// # let (node, cost) = (Vec3::ZERO, 1.5);
+/// debug!(gizmo = label(node, format!("{cost:.1}"), WHITE));
+/// ```
+///
+/// Labels are only drawn by [`GizmoLayer`] with the `labels` feature.
+/// [`GizmoSvg`] and [`GizmoImage`] ignore them.
+///
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`GizmoSvg`]: crate::GizmoSvg
+/// [`GizmoImage`]: crate::GizmoImage
+pub fn label(position: Vec3, text: impl Into<String>, color: impl Into<Color>) -> String {
+    typed::label(position, text, color).into()
 }
 
 /// Gizmo log version of [`line`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.line).
 pub fn line(start: Vec3, end: Vec3, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Line {
-        start,
-        end,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::line(start, end, color).into()
 }
 
 /// Gizmo log version of [`line_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.line_2d).
 pub fn line_2d(start: Vec2, end: Vec2, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Line2d {
-        start,
-        end,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::line_2d(start, end, color).into()
 }
 
 /// Gizmo log version of [`line_gradient`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.line_gradient).
@@ -604,13 +1022,7 @@ pub fn line_gradient(
     start_color: impl Into<Color>,
     end_color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::LineGradient {
-        start,
-        end,
-        start_color: start_color.into(),
-        end_color: end_color.into(),
-    })
-    .unwrap()
+    typed::line_gradient(start, end, start_color, end_color).into()
 }
 
 /// Gizmo log version of [`line_gradient_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.line_gradient_2d).
@@ -620,80 +1032,76 @@ pub fn line_gradient_2d(
     start_color: impl Into<Color>,
     end_color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::LineGradient2d {
-        start,
-        end,
-        start_color: start_color.into(),
-        end_color: end_color.into(),
-    })
-    .unwrap()
+    typed::line_gradient_2d(start, end, start_color, end_color).into()
 }
 
 /// Gizmo log version of [`linestrip`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.linestrip).
 pub fn linestrip(positions: impl IntoIterator<Item = Vec3>, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Linestrip {
-        positions: positions.into_iter().collect(),
-        color: color.into(),
-    })
-    .unwrap()
+    typed::linestrip(positions, color).into()
 }
 
 /// Gizmo log version of [`linestrip_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.linestrip_2d).
 pub fn linestrip_2d(positions: impl IntoIterator<Item = Vec2>, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Linestrip2d {
-        positions: positions.into_iter().collect(),
-        color: color.into(),
-    })
-    .unwrap()
+    typed::linestrip_2d(positions, color).into()
 }
 
 /// Gizmo log version of [`linestrip_gradient`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.linestrip_gradient).
 pub fn linestrip_gradient<C: Into<Color>>(points: impl IntoIterator<Item = (Vec3, C)>) -> String {
-    ron::ser::to_string(&GizmoCommand::LinestripGradient {
-        points: points.into_iter().map(|(v, c)| (v, c.into())).collect(),
-    })
-    .unwrap()
+    typed::linestrip_gradient(points).into()
 }
 
 /// Gizmo log version of [`linestrip_gradient_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.linestrip_gradient_2d).
 pub fn linestrip_gradient_2d<C: Into<Color>>(
     positions: impl IntoIterator<Item = (Vec2, C)>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::LinestripGradient2d {
-        positions: positions.into_iter().map(|(v, c)| (v, c.into())).collect(),
-    })
-    .unwrap()
+    typed::linestrip_gradient_2d(positions).into()
 }
 
 /// Gizmo log version of [`long_arc_3d_between`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.long_arc_3d_between).
 pub fn long_arc_3d_between(center: Vec3, from: Vec3, to: Vec3, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::LongArc3dBetween {
-        center,
-        from,
-        to,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::long_arc_3d_between(center, from, to, color).into()
+}
+
+/// Gizmo log version of [`primitive_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/primitives/dim2/trait.GizmoPrimitive2d.html#tymethod.primitive_2d).
+///
+/// `primitive` is any shape from [`bevy_math::primitives`] that converts into a [`Shape2d`].
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_log::debug;
+/// # use bevy_math::{primitives::Capsule2d, Isometry2d, Vec2};
+/// use bevy_gizmo_log::gizmo::primitive_2d;
+///
+/// let capsule = Capsule2d::new(0.5, 2.0);
+/// debug!(gizmo = primitive_2d(capsule, Isometry2d::from_translation(Vec2::X), RED));
+/// ```
+pub fn primitive_2d(
+    primitive: impl Into<Shape2d>,
+    isometry: impl Into<Isometry2d>,
+    color: impl Into<Color>,
+) -> String {
+    typed::primitive_2d(primitive, isometry, color).into()
+}
+
+/// Gizmo log version of [`primitive_3d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/primitives/dim3/trait.GizmoPrimitive3d.html#tymethod.primitive_3d).
+///
+/// `primitive` is any shape from [`bevy_math::primitives`] that converts into a [`Shape3d`].
+pub fn primitive_3d(
+    primitive: impl Into<Shape3d>,
+    isometry: impl Into<Isometry3d>,
+    color: impl Into<Color>,
+) -> String {
+    typed::primitive_3d(primitive, isometry, color).into()
 }
 
 /// Gizmo log version of [`ray`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ray).
 pub fn ray(start: Vec3, vector: Vec3, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Ray {
-        start,
-        vector,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::ray(start, vector, color).into()
 }
 
 /// Gizmo log version of [`ray_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ray_2d).
 pub fn ray_2d(start: Vec2, vector: Vec2, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Ray2d {
-        start,
-        vector,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::ray_2d(start, vector, color).into()
 }
 
 /// Gizmo log version of [`ray_gradient`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ray_gradient).
@@ -703,13 +1111,7 @@ pub fn ray_gradient(
     start_color: impl Into<Color>,
     end_color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::RayGradient {
-        start,
-        vector,
-        start_color: start_color.into(),
-        end_color: end_color.into(),
-    })
-    .unwrap()
+    typed::ray_gradient(start, vector, start_color, end_color).into()
 }
 
 /// Gizmo log version of [`ray_gradient_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ray_gradient_2d).
@@ -719,33 +1121,17 @@ pub fn ray_gradient_2d(
     start_color: impl Into<Color>,
     end_color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::RayGradient2d {
-        start,
-        vector,
-        start_color: start_color.into(),
-        end_color: end_color.into(),
-    })
-    .unwrap()
+    typed::ray_gradient_2d(start, vector, start_color, end_color).into()
 }
 
 /// Gizmo log version of [`rect`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.rect).
 pub fn rect(isometry: impl Into<Isometry3d>, size: Vec2, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Rect {
-        isometry: isometry.into(),
-        size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::rect(isometry, size, color).into()
 }
 
 /// Gizmo log version of [`rect_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.rect_2d).
 pub fn rect_2d(isometry: impl Into<Isometry2d>, size: Vec2, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Rect2d {
-        isometry: isometry.into(),
-        size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::rect_2d(isometry, size, color).into()
 }
 
 /// Gizmo log version of [`rounded_cuboid`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.rounded_cuboid).
@@ -754,12 +1140,7 @@ pub fn rounded_cuboid(
     size: Vec3,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::RoundedCuboid {
-        isometry: isometry.into(),
-        size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::rounded_cuboid(isometry, size, color).into()
 }
 
 /// Gizmo log version of [`rounded_rect`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.rounded_rect).
@@ -768,12 +1149,7 @@ pub fn rounded_rect(
     size: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::RoundedRect {
-        isometry: isometry.into(),
-        size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::rounded_rect(isometry, size, color).into()
 }
 
 /// Gizmo log version of [`rounded_rect_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.rounded_rect_2d).
@@ -782,31 +1158,45 @@ pub fn rounded_rect_2d(
     size: Vec2,
     color: impl Into<Color>,
 ) -> String {
-    ron::ser::to_string(&GizmoCommand::RoundedRect2d {
-        isometry: isometry.into(),
-        size,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::rounded_rect_2d(isometry, size, color).into()
 }
 
 /// Gizmo log version of [`short_arc_3d_between`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.short_arc_3d_between).
 pub fn short_arc_3d_between(center: Vec3, from: Vec3, to: Vec3, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::ShortArc3dBetween {
-        center,
-        from,
-        to,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::short_arc_3d_between(center, from, to, color).into()
 }
 
 /// Gizmo log version of [`sphere`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.sphere).
 pub fn sphere(isometry: impl Into<Isometry3d>, radius: f32, color: impl Into<Color>) -> String {
-    ron::ser::to_string(&GizmoCommand::Sphere {
-        isometry: isometry.into(),
-        radius,
-        color: color.into(),
-    })
-    .unwrap()
+    typed::sphere(isometry, radius, color).into()
+}
+
+/// Value of a `gizmo.transform` span field, which sets the local coordinate
+/// frame of the gizmos logged inside the span.
+///
+/// Gizmos are transformed by the frames of all their enclosing spans, from
+/// the outermost to the innermost.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::gizmo::{sphere, transform};
+/// # use bevy_log::{debug, info_span};
+/// # use bevy_math::Vec3;
+/// # use bevy_transform::components::Transform;
+/// let agent = Transform::from_xyz(5.0, 0.0, 0.0);
+/// let _span = info_span!("agent", gizmo.transform = transform(agent)).entered();
+/// // Drawn at (5, 1, 0)
+/// debug!(gizmo = sphere(Vec3::Y, 1.0, RED));
+/// ```
+///
+/// Only strings returned by `gizmo::transform(..)` are read, logged as is or
+/// with `%`. Other values, such as a [`Mat4`] logged with `?`, are ignored.
+///
+/// The span must be enabled for [`GizmoLayer`]. Its filter from
+/// [`GizmoFilter::reloadable`] enables spans at every level.
+///
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`GizmoFilter::reloadable`]: crate::GizmoFilter::reloadable
+pub fn transform(transform: impl IntoMat4) -> String {
+    ron::ser::to_string(&transform.into_mat4()).unwrap()
 }
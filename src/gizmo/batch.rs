@@ -0,0 +1,145 @@
+use std::{borrow::Cow, fmt};
+
+use super::{typed::fmt_command, GizmoCommand, GizmoValue};
+
+/// Many gizmos logged as one event.
+///
+/// Every log event goes through filtering, the subscriber's layers and
+/// [`GizmoLayer`] on its own. When logging many gizmos per frame, add them
+/// to a batch and log the batch once, like a [`GizmoValue`]:
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::gizmo::{typed::line_2d, GizmoBatch};
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec2;
+/// let mut batch = GizmoBatch::new();
+/// for i in 0..250 {
+///     let x = i as f32;
+///     batch.add(line_2d(Vec2::new(x, 0.), Vec2::new(x, 100.), RED));
+/// }
+/// debug!(gizmo = %batch);
+/// ```
+///
+/// The fields of the event, such as `gizmo.key` or `gizmo.ttl_secs`, apply
+/// to the whole batch. Gizmos such as [`typed::clear`] that clear retained
+/// gizmos are applied before the rest of the batch is drawn or retained.
+///
+/// [`typed::clear`]: super::typed::clear
+///
+/// [`GizmoLayer`]: crate::GizmoLayer
+#[derive(Clone)]
+pub struct GizmoBatch(Vec<GizmoCommand>);
+
+impl GizmoBatch {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create an empty batch with room for `capacity` gizmos.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Add a gizmo to the batch.
+    pub fn add(&mut self, gizmo: GizmoValue) -> &mut Self {
+        self.0.push(gizmo.0);
+        self
+    }
+
+    /// The number of gizmos in the batch.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the batch has no gizmos.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Remove every gizmo from the batch, to reuse it in the next frame.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl Default for GizmoBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<GizmoValue> for GizmoBatch {
+    fn extend<I: IntoIterator<Item = GizmoValue>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|gizmo| gizmo.0));
+    }
+}
+
+impl FromIterator<GizmoValue> for GizmoBatch {
+    fn from_iter<I: IntoIterator<Item = GizmoValue>>(iter: I) -> Self {
+        let mut batch = Self::new();
+        batch.extend(iter);
+        batch
+    }
+}
+
+impl GizmoBatch {
+    fn command(&self) -> GizmoCommand {
+        GizmoCommand::Batch {
+            gizmos: self.0.clone(),
+        }
+    }
+}
+
+impl fmt::Display for GizmoBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_command(Cow::Owned(self.command()), f)
+    }
+}
+
+impl fmt::Debug for GizmoBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_command(Cow::Owned(self.command()), f)
+    }
+}
+
+impl From<GizmoBatch> for GizmoValue {
+    fn from(batch: GizmoBatch) -> Self {
+        GizmoValue(GizmoCommand::Batch { gizmos: batch.0 })
+    }
+}
+
+impl From<GizmoBatch> for String {
+    fn from(batch: GizmoBatch) -> Self {
+        GizmoValue::from(batch).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::Vec3;
+
+    use super::*;
+    use crate::{gizmo::typed::line, with_captured_gizmos};
+
+    #[test]
+    fn test_batch() {
+        let batch: GizmoBatch = (0..3)
+            .map(|i| line(Vec3::ZERO, Vec3::splat(i as f32), RED))
+            .collect();
+        let gizmos = with_captured_gizmos(|| {
+            tracing::debug!(gizmo = %batch);
+            tracing::debug!(gizmo = String::from(batch.clone()));
+        });
+        assert_eq!(gizmos.len(), 2);
+        assert!(gizmos
+            .iter()
+            .all(|gizmo| gizmo == &GizmoValue::from(batch.clone()).0));
+        let GizmoCommand::Batch { gizmos } = &gizmos[0] else {
+            unreachable!();
+        };
+        assert_eq!(gizmos.len(), 3);
+    }
+}
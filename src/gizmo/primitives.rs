@@ -0,0 +1,389 @@
+//! Serializable versions of the shapes in [`bevy_math::primitives`].
+
+#[cfg(feature = "bevy")]
+use bevy_color::Color;
+#[cfg(feature = "bevy")]
+use bevy_gizmos::{
+    config::GizmoConfigGroup,
+    gizmos::Gizmos,
+    primitives::{dim2::GizmoPrimitive2d, dim3::GizmoPrimitive3d},
+};
+use bevy_math::{
+    cubic_splines::{CubicBezier, CubicGenerator},
+    primitives::{
+        Annulus, Arc2d, BoxedPolygon, BoxedPolyline2d, BoxedPolyline3d, Capsule2d, Capsule3d,
+        Circle, CircularSector, CircularSegment, Cone, ConicalFrustum, ConvexPolygon, Cuboid,
+        Cylinder, Ellipse, Line2d, Line3d, Plane2d, Plane3d, Polygon, Polyline2d, Polyline3d,
+        Rectangle, RegularPolygon, Rhombus, Segment2d, Segment3d, Sphere, Tetrahedron, Torus,
+        Triangle2d, Triangle3d,
+    },
+    VectorSpace,
+};
+#[cfg(feature = "bevy")]
+use bevy_math::{Isometry2d, Isometry3d};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number of lines drawn for each segment of a cubic Bézier curve.
+const CUBIC_BEZIER_RESOLUTION: usize = 32;
+
+/// A 2D shape from [`bevy_math::primitives`], logged with [`primitive_2d`](super::primitive_2d).
+///
+/// Every shape that bevy can draw as a 2D gizmo converts into a [`Shape2d`].
+/// Shapes with a fixed number of vertices are stored as their boxed
+/// equivalent, so a [`Polygon`] or a [`ConvexPolygon`] becomes a [`BoxedPolygon`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Shape2d {
+    Annulus(Annulus),
+    Arc2d(Arc2d),
+    BoxedPolygon(BoxedPolygon),
+    BoxedPolyline2d(BoxedPolyline2d),
+    Capsule2d(Capsule2d),
+    Circle(Circle),
+    CircularSector(#[serde(with = "as_arc")] CircularSector),
+    CircularSegment(#[serde(with = "as_arc")] CircularSegment),
+    Ellipse(Ellipse),
+    Line2d(Line2d),
+    Plane2d(Plane2d),
+    Rectangle(Rectangle),
+    RegularPolygon(RegularPolygon),
+    Rhombus(Rhombus),
+    Segment2d(Segment2d),
+    Triangle2d(Triangle2d),
+}
+
+/// A 3D shape from [`bevy_math::primitives`], logged with [`primitive_3d`](super::primitive_3d).
+///
+/// Every shape that bevy can draw as a 3D gizmo converts into a [`Shape3d`].
+/// A [`Polyline3d`] is stored as a [`BoxedPolyline3d`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Shape3d {
+    BoxedPolyline3d(BoxedPolyline3d),
+    Capsule3d(Capsule3d),
+    Cone(Cone),
+    ConicalFrustum(ConicalFrustum),
+    Cuboid(Cuboid),
+    Cylinder(Cylinder),
+    Line3d(Line3d),
+    Plane3d(Plane3d),
+    Segment3d(Segment3d),
+    Sphere(Sphere),
+    Tetrahedron(Tetrahedron),
+    Torus(Torus),
+    Triangle3d(Triangle3d),
+}
+
+macro_rules! impl_from_primitive {
+    ($shape:ident: $($primitive:ident),* $(,)?) => {
+        $(
+            impl From<$primitive> for $shape {
+                fn from(primitive: $primitive) -> Self {
+                    $shape::$primitive(primitive)
+                }
+            }
+
+            impl From<&$primitive> for $shape {
+                fn from(primitive: &$primitive) -> Self {
+                    $shape::$primitive(primitive.clone())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_primitive!(Shape2d:
+    Annulus,
+    Arc2d,
+    BoxedPolygon,
+    BoxedPolyline2d,
+    Capsule2d,
+    Circle,
+    CircularSector,
+    CircularSegment,
+    Ellipse,
+    Line2d,
+    Plane2d,
+    Rectangle,
+    RegularPolygon,
+    Rhombus,
+    Segment2d,
+    Triangle2d,
+);
+
+impl_from_primitive!(Shape3d:
+    BoxedPolyline3d,
+    Capsule3d,
+    Cone,
+    ConicalFrustum,
+    Cuboid,
+    Cylinder,
+    Line3d,
+    Plane3d,
+    Segment3d,
+    Sphere,
+    Tetrahedron,
+    Torus,
+    Triangle3d,
+);
+
+impl<const N: usize> From<Polygon<N>> for Shape2d {
+    fn from(polygon: Polygon<N>) -> Self {
+        Shape2d::BoxedPolygon(BoxedPolygon::new(polygon.vertices))
+    }
+}
+
+impl<const N: usize> From<&Polygon<N>> for Shape2d {
+    fn from(polygon: &Polygon<N>) -> Self {
+        Shape2d::BoxedPolygon(BoxedPolygon::new(polygon.vertices))
+    }
+}
+
+impl<const N: usize> From<ConvexPolygon<N>> for Shape2d {
+    fn from(polygon: ConvexPolygon<N>) -> Self {
+        Shape2d::BoxedPolygon(BoxedPolygon::new(*polygon.vertices()))
+    }
+}
+
+impl<const N: usize> From<&ConvexPolygon<N>> for Shape2d {
+    fn from(polygon: &ConvexPolygon<N>) -> Self {
+        Shape2d::BoxedPolygon(BoxedPolygon::new(*polygon.vertices()))
+    }
+}
+
+impl<const N: usize> From<Polyline2d<N>> for Shape2d {
+    fn from(polyline: Polyline2d<N>) -> Self {
+        Shape2d::BoxedPolyline2d(BoxedPolyline2d::new(polyline.vertices))
+    }
+}
+
+impl<const N: usize> From<&Polyline2d<N>> for Shape2d {
+    fn from(polyline: &Polyline2d<N>) -> Self {
+        Shape2d::BoxedPolyline2d(BoxedPolyline2d::new(polyline.vertices))
+    }
+}
+
+impl<const N: usize> From<Polyline3d<N>> for Shape3d {
+    fn from(polyline: Polyline3d<N>) -> Self {
+        Shape3d::BoxedPolyline3d(BoxedPolyline3d::new(polyline.vertices))
+    }
+}
+
+impl<const N: usize> From<&Polyline3d<N>> for Shape3d {
+    fn from(polyline: &Polyline3d<N>) -> Self {
+        Shape3d::BoxedPolyline3d(BoxedPolyline3d::new(polyline.vertices))
+    }
+}
+
+/// A shape that is fully described by an [`Arc2d`].
+trait ArcShape {
+    fn arc(&self) -> Arc2d;
+    fn from_arc(arc: Arc2d) -> Self;
+}
+
+impl ArcShape for CircularSector {
+    fn arc(&self) -> Arc2d {
+        self.arc
+    }
+
+    fn from_arc(arc: Arc2d) -> Self {
+        CircularSector { arc }
+    }
+}
+
+impl ArcShape for CircularSegment {
+    fn arc(&self) -> Arc2d {
+        self.arc
+    }
+
+    fn from_arc(arc: Arc2d) -> Self {
+        CircularSegment { arc }
+    }
+}
+
+/// Serializes circular sectors and segments as their arc.
+///
+/// Bevy flattens the arc into them, which RON serializes as a map that it
+/// can't deserialize back.
+mod as_arc {
+    use super::*;
+
+    pub(super) fn serialize<T: ArcShape, S: Serializer>(
+        shape: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        shape.arc().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, T: ArcShape, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        Arc2d::deserialize(deserializer).map(T::from_arc)
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl Shape2d {
+    /// Draw the shape with [`GizmoPrimitive2d::primitive_2d`].
+    pub(crate) fn draw<Config: GizmoConfigGroup>(
+        &self,
+        gizmos: &mut Gizmos<'_, '_, Config>,
+        isometry: Isometry2d,
+        color: Color,
+    ) {
+        match self {
+            Shape2d::Annulus(p) => {
+                gizmos.primitive_2d(p, isometry, color);
+            }
+            Shape2d::Arc2d(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::BoxedPolygon(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::BoxedPolyline2d(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Capsule2d(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Circle(p) => {
+                gizmos.primitive_2d(p, isometry, color);
+            }
+            Shape2d::CircularSector(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::CircularSegment(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Ellipse(p) => {
+                gizmos.primitive_2d(p, isometry, color);
+            }
+            Shape2d::Line2d(p) => {
+                gizmos.primitive_2d(p, isometry, color);
+            }
+            Shape2d::Plane2d(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Rectangle(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::RegularPolygon(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Rhombus(p) => gizmos.primitive_2d(p, isometry, color),
+            Shape2d::Segment2d(p) => {
+                gizmos.primitive_2d(p, isometry, color);
+            }
+            Shape2d::Triangle2d(p) => gizmos.primitive_2d(p, isometry, color),
+        }
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl Shape3d {
+    /// Draw the shape with [`GizmoPrimitive3d::primitive_3d`].
+    pub(crate) fn draw<Config: GizmoConfigGroup>(
+        &self,
+        gizmos: &mut Gizmos<'_, '_, Config>,
+        isometry: Isometry3d,
+        color: Color,
+    ) {
+        match self {
+            Shape3d::BoxedPolyline3d(p) => gizmos.primitive_3d(p, isometry, color),
+            Shape3d::Capsule3d(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Cone(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::ConicalFrustum(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Cuboid(p) => gizmos.primitive_3d(p, isometry, color),
+            Shape3d::Cylinder(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Line3d(p) => gizmos.primitive_3d(p, isometry, color),
+            Shape3d::Plane3d(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Segment3d(p) => gizmos.primitive_3d(p, isometry, color),
+            Shape3d::Sphere(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Tetrahedron(p) => gizmos.primitive_3d(p, isometry, color),
+            Shape3d::Torus(p) => {
+                gizmos.primitive_3d(p, isometry, color);
+            }
+            Shape3d::Triangle3d(p) => gizmos.primitive_3d(p, isometry, color),
+        }
+    }
+}
+
+/// Points along a chain of cubic Bézier segments, which are drawn as a linestrip.
+pub(crate) fn cubic_bezier_positions<P: VectorSpace>(control_points: &[[P; 4]]) -> Vec<P> {
+    match CubicBezier::new(control_points).to_curve() {
+        Ok(curve) => curve
+            .iter_positions(control_points.len() * CUBIC_BEZIER_RESOLUTION)
+            .collect(),
+        // There are no segments to draw.
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::{Dir2, Dir3, Isometry2d, Isometry3d, Vec2, Vec3};
+
+    use super::*;
+    use crate::gizmo::{typed, wire};
+
+    #[test]
+    fn test_round_trip() {
+        let shapes_2d: Vec<Shape2d> = vec![
+            Annulus::new(1.0, 2.0).into(),
+            Arc2d::new(1.0, 0.5).into(),
+            Polygon::<3>::new([Vec2::ZERO, Vec2::X, Vec2::Y]).into(),
+            Polyline2d::<3>::new([Vec2::ZERO, Vec2::X, Vec2::Y]).into(),
+            Capsule2d::new(0.5, 2.0).into(),
+            Circle::new(1.0).into(),
+            CircularSector::new(1.0, 0.5).into(),
+            CircularSegment::new(1.0, 0.5).into(),
+            Ellipse::new(1.0, 2.0).into(),
+            Line2d { direction: Dir2::X }.into(),
+            Plane2d::new(Vec2::Y).into(),
+            Rectangle::new(1.0, 2.0).into(),
+            RegularPolygon::new(1.0, 6).into(),
+            Rhombus::new(1.0, 2.0).into(),
+            Segment2d::new(Dir2::X, 2.0).into(),
+            Triangle2d::new(Vec2::ZERO, Vec2::X, Vec2::Y).into(),
+        ];
+        for shape in shapes_2d {
+            let value = typed::primitive_2d(shape, Isometry2d::IDENTITY, RED);
+            assert_eq!(wire::decode(&value.to_string()).unwrap(), value.0);
+            #[cfg(feature = "cbor")]
+            assert_eq!(
+                wire::decode(&wire::encode_with(&value.0, wire::Encoding::Cbor)).unwrap(),
+                value.0
+            );
+        }
+
+        let shapes_3d: Vec<Shape3d> = vec![
+            Polyline3d::<3>::new([Vec3::ZERO, Vec3::X, Vec3::Y]).into(),
+            Capsule3d::new(0.5, 2.0).into(),
+            Cone::new(1.0, 2.0).into(),
+            ConicalFrustum::default().into(),
+            Cuboid::new(1.0, 2.0, 3.0).into(),
+            Cylinder::new(1.0, 2.0).into(),
+            Line3d { direction: Dir3::X }.into(),
+            Plane3d::new(Vec3::Y, Vec2::ONE).into(),
+            Segment3d::new(Dir3::X, 2.0).into(),
+            Sphere::new(1.0).into(),
+            Tetrahedron::default().into(),
+            Torus::new(1.0, 2.0).into(),
+            Triangle3d::new(Vec3::ZERO, Vec3::X, Vec3::Y).into(),
+        ];
+        for shape in shapes_3d {
+            let value = typed::primitive_3d(shape, Isometry3d::IDENTITY, RED);
+            assert_eq!(wire::decode(&value.to_string()).unwrap(), value.0);
+            #[cfg(feature = "cbor")]
+            assert_eq!(
+                wire::decode(&wire::encode_with(&value.0, wire::Encoding::Cbor)).unwrap(),
+                value.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_cubic_bezier_positions() {
+        let positions = cubic_bezier_positions(&[[Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]; 2]);
+        assert_eq!(positions.len(), 2 * CUBIC_BEZIER_RESOLUTION + 1);
+        assert_eq!(positions[0], Vec2::ZERO);
+        assert!(positions.last().unwrap().distance(Vec2::Y) < 1e-5);
+        assert!(cubic_bezier_positions::<Vec2>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,830 @@
+//! Typed versions of the functions in [`gizmo`](super).
+//!
+//! The functions in [`gizmo`](super) return strings, which [`GizmoLayer`]
+//! has to parse back into gizmos. The functions in this module instead
+//! return a [`GizmoValue`], which [`GizmoLayer`] receives without
+//! formatting or parsing any text. This is faster when logging many gizmos.
+//!
+//! Log a [`GizmoValue`] with the `%` or `?` sigil:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! use bevy_gizmo_log::gizmo::typed::arrow;
+//!
+//! debug!(gizmo = %arrow(Vec3::ZERO, Vec3::ONE, RED));
+//! ```
+//!
+//! Subscribers other than [`GizmoLayer`] see a [`GizmoValue`] as the same
+//! string that the equivalent function in [`gizmo`](super) would return.
+//!
+//! [`GizmoLayer`]: crate::GizmoLayer
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+use bevy_color::Color;
+use bevy_math::{Isometry2d, Isometry3d, UVec2, UVec3, Vec2, Vec3};
+
+use super::{GizmoCommand, GizmoKey, IntoMat4, Shape2d, Shape3d};
+
+/// A gizmo that can be logged without being converted to a string.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Clone)]
+pub struct GizmoValue(pub(crate) GizmoCommand);
+
+thread_local! {
+    /// Set while a visitor is capturing a [`GizmoValue`] from an event.
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    /// Where a [`GizmoValue`] puts its command while it is being captured.
+    static CAPTURED: RefCell<Option<GizmoCommand>> = const { RefCell::new(None) };
+}
+
+/// Extract the command from a field value if it is a [`GizmoValue`].
+///
+/// Tracing only exposes non-primitive field values as `dyn Debug`, so
+/// this formats the value with a flag set that makes [`GizmoValue`]
+/// hand over its command instead of writing any text.
+pub(crate) fn capture(value: &dyn fmt::Debug) -> Option<GizmoCommand> {
+    struct Discard;
+
+    impl fmt::Write for Discard {
+        fn write_str(&mut self, _s: &str) -> fmt::Result {
+            Ok(())
+        }
+    }
+
+    /// Clears the flag even if formatting the value panics.
+    struct Capturing;
+
+    impl Drop for Capturing {
+        fn drop(&mut self) {
+            CAPTURING.set(false);
+        }
+    }
+
+    CAPTURED.take();
+    let capturing = Capturing;
+    CAPTURING.set(true);
+    let _ = fmt::write(&mut Discard, format_args!("{value:?}"));
+    drop(capturing);
+    CAPTURED.take()
+}
+
+/// Write `command` in the wire format, or hand it over if it is being captured.
+pub(super) fn fmt_command(
+    command: Cow<'_, GizmoCommand>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    if CAPTURING.get() {
+        CAPTURED.set(Some(command.into_owned()));
+        return Ok(());
+    }
+    f.write_str(&super::wire::encode(&command))
+}
+
+/// Builder options, named after the options of the builders returned by
+/// bevy's [`Gizmos`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html)
+/// methods.
+///
+/// Each option only applies to the gizmos whose bevy builder has it, and is
+/// ignored by every other gizmo. Use [`String::from`] to log a configured
+/// gizmo as a string.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// use bevy_gizmo_log::gizmo::typed::{arrow, sphere};
+///
+/// debug!(gizmo = %sphere(Vec3::ZERO, 1.0, RED).resolution(64));
+/// debug!(gizmo = %arrow(Vec3::ZERO, Vec3::ONE, RED).with_tip_length(0.3).with_double_end());
+/// ```
+impl GizmoValue {
+    /// Set the number of line segments of an arc, circle, ellipse or sphere.
+    pub fn resolution(mut self, resolution: u32) -> Self {
+        match &mut self.0 {
+            GizmoCommand::Arc2d { resolution: r, .. }
+            | GizmoCommand::Arc3d { resolution: r, .. }
+            | GizmoCommand::Circle { resolution: r, .. }
+            | GizmoCommand::Circle2d { resolution: r, .. }
+            | GizmoCommand::Ellipse { resolution: r, .. }
+            | GizmoCommand::Ellipse2d { resolution: r, .. }
+            | GizmoCommand::LongArc3dBetween { resolution: r, .. }
+            | GizmoCommand::ShortArc3dBetween { resolution: r, .. }
+            | GizmoCommand::Sphere { resolution: r, .. } => *r = Some(resolution),
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the length of an arrow's tip. Defaults to a tenth of the arrow's length.
+    pub fn with_tip_length(mut self, length: f32) -> Self {
+        match &mut self.0 {
+            GizmoCommand::Arrow { tip_length, .. } | GizmoCommand::Arrow2d { tip_length, .. } => {
+                *tip_length = Some(length)
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Draw a tip at the start of an arrow as well as at its end.
+    pub fn with_double_end(mut self) -> Self {
+        match &mut self.0 {
+            GizmoCommand::Arrow { double_ended, .. }
+            | GizmoCommand::Arrow2d { double_ended, .. } => *double_ended = true,
+            _ => {}
+        }
+        self
+    }
+
+    /// Skew a grid by the given angles in radians. 2D grids ignore `z`.
+    pub fn skew(self, skew: Vec3) -> Self {
+        self.map_skew(|_| skew)
+    }
+
+    /// Skew a grid along its x axis by the given angle in radians.
+    pub fn skew_x(self, angle: f32) -> Self {
+        self.map_skew(|skew| skew.with_x(angle))
+    }
+
+    /// Skew a grid along its y axis by the given angle in radians.
+    pub fn skew_y(self, angle: f32) -> Self {
+        self.map_skew(|skew| skew.with_y(angle))
+    }
+
+    /// Skew a 3D grid along its z axis by the given angle in radians.
+    pub fn skew_z(self, angle: f32) -> Self {
+        self.map_skew(|skew| skew.with_z(angle))
+    }
+
+    fn map_skew(mut self, f: impl FnOnce(Vec3) -> Vec3) -> Self {
+        match &mut self.0 {
+            GizmoCommand::Grid { skew, .. } | GizmoCommand::Grid2d { skew, .. } => {
+                *skew = f(skew.extend(0.)).truncate()
+            }
+            GizmoCommand::Grid3d { skew, .. } => *skew = f(*skew),
+            _ => {}
+        }
+        self
+    }
+
+    /// Draw the outer edges of a grid along every axis.
+    pub fn outer_edges(self) -> Self {
+        self.map_outer_edges(|_| [true; 3])
+    }
+
+    /// Draw the outer edges of a grid along its x axis.
+    pub fn outer_edges_x(self) -> Self {
+        self.map_outer_edges(|[_, y, z]| [true, y, z])
+    }
+
+    /// Draw the outer edges of a grid along its y axis.
+    pub fn outer_edges_y(self) -> Self {
+        self.map_outer_edges(|[x, _, z]| [x, true, z])
+    }
+
+    /// Draw the outer edges of a 3D grid along its z axis.
+    pub fn outer_edges_z(self) -> Self {
+        self.map_outer_edges(|[x, y, _]| [x, y, true])
+    }
+
+    fn map_outer_edges(mut self, f: impl FnOnce([bool; 3]) -> [bool; 3]) -> Self {
+        match &mut self.0 {
+            GizmoCommand::Grid { outer_edges, .. } | GizmoCommand::Grid2d { outer_edges, .. } => {
+                let [x, y, _] = f([outer_edges[0], outer_edges[1], false]);
+                *outer_edges = [x, y];
+            }
+            GizmoCommand::Grid3d { outer_edges, .. } => *outer_edges = f(*outer_edges),
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the corner radius of a rounded rectangle.
+    /// Defaults to a tenth of its shortest side.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        match &mut self.0 {
+            GizmoCommand::RoundedRect { corner_radius, .. }
+            | GizmoCommand::RoundedRect2d { corner_radius, .. } => *corner_radius = Some(radius),
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the edge radius of a rounded cuboid.
+    /// Defaults to a tenth of its shortest side.
+    pub fn edge_radius(mut self, radius: f32) -> Self {
+        if let GizmoCommand::RoundedCuboid { edge_radius, .. } = &mut self.0 {
+            *edge_radius = Some(radius);
+        }
+        self
+    }
+
+    /// Set the number of line segments of each rounded corner of a rounded
+    /// rectangle or cuboid. Defaults to 8.
+    pub fn arc_resolution(mut self, resolution: u32) -> Self {
+        match &mut self.0 {
+            GizmoCommand::RoundedCuboid { arc_resolution, .. }
+            | GizmoCommand::RoundedRect { arc_resolution, .. }
+            | GizmoCommand::RoundedRect2d { arc_resolution, .. } => {
+                *arc_resolution = Some(resolution)
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for GizmoValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_command(Cow::Borrowed(&self.0), f)
+    }
+}
+
+impl fmt::Debug for GizmoValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_command(Cow::Borrowed(&self.0), f)
+    }
+}
+
+impl From<GizmoValue> for String {
+    fn from(value: GizmoValue) -> Self {
+        super::wire::encode(&value.0)
+    }
+}
+
+/// Typed version of [`arc_2d`](super::arc_2d).
+pub fn arc_2d(
+    isometry: impl Into<Isometry2d>,
+    arc_angle: f32,
+    radius: f32,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Arc2d {
+        isometry: isometry.into(),
+        arc_angle,
+        radius,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`arc_3d`](super::arc_3d).
+pub fn arc_3d(
+    angle: f32,
+    radius: f32,
+    isometry: impl Into<Isometry3d>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Arc3d {
+        angle,
+        radius,
+        isometry: isometry.into(),
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`arrow`](super::arrow).
+pub fn arrow(start: Vec3, end: Vec3, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Arrow {
+        start,
+        end,
+        color: color.into(),
+        tip_length: None,
+        double_ended: false,
+    })
+}
+
+/// Typed version of [`arrow_2d`](super::arrow_2d).
+pub fn arrow_2d(start: Vec2, end: Vec2, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Arrow2d {
+        start,
+        end,
+        color: color.into(),
+        tip_length: None,
+        double_ended: false,
+    })
+}
+
+/// Typed version of [`axes`](super::axes).
+pub fn axes(transform: impl IntoMat4, base_length: f32) -> GizmoValue {
+    GizmoValue(GizmoCommand::Axes {
+        transform: transform.into_mat4(),
+        base_length,
+    })
+}
+
+/// Typed version of [`axes_2d`](super::axes_2d).
+pub fn axes_2d(transform: impl IntoMat4, base_length: f32) -> GizmoValue {
+    GizmoValue(GizmoCommand::Axes2d {
+        transform: transform.into_mat4(),
+        base_length,
+    })
+}
+
+/// Typed version of [`circle`](super::circle).
+pub fn circle(isometry: impl Into<Isometry3d>, radius: f32, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Circle {
+        isometry: isometry.into(),
+        radius,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`circle_2d`](super::circle_2d).
+pub fn circle_2d(position: Vec2, radius: f32, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Circle2d {
+        position,
+        radius,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`clear`](super::clear).
+pub fn clear(key: impl Into<GizmoKey>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Clear {
+        key: Some(key.into()),
+    })
+}
+
+/// Typed version of [`clear_all`](super::clear_all).
+pub fn clear_all() -> GizmoValue {
+    GizmoValue(GizmoCommand::Clear { key: None })
+}
+
+/// Typed version of [`cubic_bezier`](super::cubic_bezier).
+pub fn cubic_bezier(
+    control_points: impl Into<Vec<[Vec3; 4]>>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::CubicBezier {
+        control_points: control_points.into(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`cubic_bezier_2d`](super::cubic_bezier_2d).
+pub fn cubic_bezier_2d(
+    control_points: impl Into<Vec<[Vec2; 4]>>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::CubicBezier2d {
+        control_points: control_points.into(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`cuboid`](super::cuboid).
+pub fn cuboid(transform: impl IntoMat4, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Cuboid {
+        transform: transform.into_mat4(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`ellipse`](super::ellipse).
+pub fn ellipse(
+    isometry: impl Into<Isometry3d>,
+    half_size: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Ellipse {
+        isometry: isometry.into(),
+        half_size,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`ellipse_2d`](super::ellipse_2d).
+pub fn ellipse_2d(
+    isometry: impl Into<Isometry2d>,
+    half_size: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Ellipse2d {
+        isometry: isometry.into(),
+        half_size,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`grid`](super::grid).
+pub fn grid(
+    isometry: impl Into<Isometry3d>,
+    cell_count: UVec2,
+    spacing: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Grid {
+        isometry: isometry.into(),
+        cell_count,
+        spacing,
+        color: color.into(),
+        skew: Vec2::ZERO,
+        outer_edges: [false; 2],
+    })
+}
+
+/// Typed version of [`grid_2d`](super::grid_2d).
+pub fn grid_2d(
+    isometry: impl Into<Isometry2d>,
+    cell_count: UVec2,
+    spacing: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Grid2d {
+        isometry: isometry.into(),
+        cell_count,
+        spacing,
+        color: color.into(),
+        skew: Vec2::ZERO,
+        outer_edges: [false; 2],
+    })
+}
+
+/// Typed version of [`grid_3d`](super::grid_3d).
+pub fn grid_3d(
+    isometry: impl Into<Isometry3d>,
+    cell_count: UVec3,
+    spacing: Vec3,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Grid3d {
+        isometry: isometry.into(),
+        cell_count,
+        spacing,
+        color: color.into(),
+        skew: Vec3::ZERO,
+        outer_edges: [false; 3],
+    })
+}
+
+/// Typed version of [`label`](super::label).
+pub fn label(position: Vec3, text: impl Into<String>, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Label {
+        position,
+        text: text.into(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`line`](super::line).
+pub fn line(start: Vec3, end: Vec3, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Line {
+        start,
+        end,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`line_2d`](super::line_2d).
+pub fn line_2d(start: Vec2, end: Vec2, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Line2d {
+        start,
+        end,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`line_gradient`](super::line_gradient).
+pub fn line_gradient(
+    start: Vec3,
+    end: Vec3,
+    start_color: impl Into<Color>,
+    end_color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::LineGradient {
+        start,
+        end,
+        start_color: start_color.into(),
+        end_color: end_color.into(),
+    })
+}
+
+/// Typed version of [`line_gradient_2d`](super::line_gradient_2d).
+pub fn line_gradient_2d(
+    start: Vec2,
+    end: Vec2,
+    start_color: impl Into<Color>,
+    end_color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::LineGradient2d {
+        start,
+        end,
+        start_color: start_color.into(),
+        end_color: end_color.into(),
+    })
+}
+
+/// Typed version of [`linestrip`](super::linestrip).
+pub fn linestrip(positions: impl IntoIterator<Item = Vec3>, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Linestrip {
+        positions: positions.into_iter().collect(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`linestrip_2d`](super::linestrip_2d).
+pub fn linestrip_2d(
+    positions: impl IntoIterator<Item = Vec2>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Linestrip2d {
+        positions: positions.into_iter().collect(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`linestrip_gradient`](super::linestrip_gradient).
+pub fn linestrip_gradient<C: Into<Color>>(
+    points: impl IntoIterator<Item = (Vec3, C)>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::LinestripGradient {
+        points: points.into_iter().map(|(v, c)| (v, c.into())).collect(),
+    })
+}
+
+/// Typed version of [`linestrip_gradient_2d`](super::linestrip_gradient_2d).
+pub fn linestrip_gradient_2d<C: Into<Color>>(
+    positions: impl IntoIterator<Item = (Vec2, C)>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::LinestripGradient2d {
+        positions: positions.into_iter().map(|(v, c)| (v, c.into())).collect(),
+    })
+}
+
+/// Typed version of [`long_arc_3d_between`](super::long_arc_3d_between).
+pub fn long_arc_3d_between(
+    center: Vec3,
+    from: Vec3,
+    to: Vec3,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::LongArc3dBetween {
+        center,
+        from,
+        to,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`primitive_2d`](super::primitive_2d).
+pub fn primitive_2d(
+    primitive: impl Into<Shape2d>,
+    isometry: impl Into<Isometry2d>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Primitive2d {
+        primitive: primitive.into(),
+        isometry: isometry.into(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`primitive_3d`](super::primitive_3d).
+pub fn primitive_3d(
+    primitive: impl Into<Shape3d>,
+    isometry: impl Into<Isometry3d>,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::Primitive3d {
+        primitive: primitive.into(),
+        isometry: isometry.into(),
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`ray`](super::ray).
+pub fn ray(start: Vec3, vector: Vec3, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Ray {
+        start,
+        vector,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`ray_2d`](super::ray_2d).
+pub fn ray_2d(start: Vec2, vector: Vec2, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Ray2d {
+        start,
+        vector,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`ray_gradient`](super::ray_gradient).
+pub fn ray_gradient(
+    start: Vec3,
+    vector: Vec3,
+    start_color: impl Into<Color>,
+    end_color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::RayGradient {
+        start,
+        vector,
+        start_color: start_color.into(),
+        end_color: end_color.into(),
+    })
+}
+
+/// Typed version of [`ray_gradient_2d`](super::ray_gradient_2d).
+pub fn ray_gradient_2d(
+    start: Vec2,
+    vector: Vec2,
+    start_color: impl Into<Color>,
+    end_color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::RayGradient2d {
+        start,
+        vector,
+        start_color: start_color.into(),
+        end_color: end_color.into(),
+    })
+}
+
+/// Typed version of [`rect`](super::rect).
+pub fn rect(isometry: impl Into<Isometry3d>, size: Vec2, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Rect {
+        isometry: isometry.into(),
+        size,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`rect_2d`](super::rect_2d).
+pub fn rect_2d(isometry: impl Into<Isometry2d>, size: Vec2, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Rect2d {
+        isometry: isometry.into(),
+        size,
+        color: color.into(),
+    })
+}
+
+/// Typed version of [`rounded_cuboid`](super::rounded_cuboid).
+pub fn rounded_cuboid(
+    isometry: impl Into<Isometry3d>,
+    size: Vec3,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::RoundedCuboid {
+        isometry: isometry.into(),
+        size,
+        color: color.into(),
+        edge_radius: None,
+        arc_resolution: None,
+    })
+}
+
+/// Typed version of [`rounded_rect`](super::rounded_rect).
+pub fn rounded_rect(
+    isometry: impl Into<Isometry3d>,
+    size: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::RoundedRect {
+        isometry: isometry.into(),
+        size,
+        color: color.into(),
+        corner_radius: None,
+        arc_resolution: None,
+    })
+}
+
+/// Typed version of [`rounded_rect_2d`](super::rounded_rect_2d).
+pub fn rounded_rect_2d(
+    isometry: impl Into<Isometry2d>,
+    size: Vec2,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::RoundedRect2d {
+        isometry: isometry.into(),
+        size,
+        color: color.into(),
+        corner_radius: None,
+        arc_resolution: None,
+    })
+}
+
+/// Typed version of [`short_arc_3d_between`](super::short_arc_3d_between).
+pub fn short_arc_3d_between(
+    center: Vec3,
+    from: Vec3,
+    to: Vec3,
+    color: impl Into<Color>,
+) -> GizmoValue {
+    GizmoValue(GizmoCommand::ShortArc3dBetween {
+        center,
+        from,
+        to,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+/// Typed version of [`sphere`](super::sphere).
+pub fn sphere(isometry: impl Into<Isometry3d>, radius: f32, color: impl Into<Color>) -> GizmoValue {
+    GizmoValue(GizmoCommand::Sphere {
+        isometry: isometry.into(),
+        radius,
+        color: color.into(),
+        resolution: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let value = arrow(Vec3::ZERO, Vec3::ONE, RED);
+        let command = capture(&value).unwrap();
+        assert!(matches!(
+            command,
+            GizmoCommand::Arrow { end: Vec3::ONE, .. }
+        ));
+
+        // Values that aren't GizmoValues capture nothing.
+        assert!(capture(&"arrow").is_none());
+    }
+
+    #[test]
+    fn test_capture_panic() {
+        struct Panics;
+
+        impl fmt::Debug for Panics {
+            fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                panic!("formatting panicked");
+            }
+        }
+
+        assert!(std::panic::catch_unwind(|| capture(&Panics)).is_err());
+        // Values are still formatted as text afterwards.
+        let value = arrow(Vec3::ZERO, Vec3::ONE, RED);
+        assert_eq!(
+            value.to_string(),
+            super::super::arrow(Vec3::ZERO, Vec3::ONE, RED)
+        );
+    }
+
+    #[test]
+    fn test_display_matches_string_path() {
+        let value = arrow(Vec3::ZERO, Vec3::ONE, RED);
+        assert_eq!(
+            value.to_string(),
+            super::super::arrow(Vec3::ZERO, Vec3::ONE, RED)
+        );
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let value = sphere(Vec3::ZERO, 1.0, RED).resolution(64);
+        assert!(matches!(
+            value.0,
+            GizmoCommand::Sphere {
+                resolution: Some(64),
+                ..
+            }
+        ));
+        assert_eq!(
+            super::super::wire::decode(&value.to_string()).unwrap(),
+            value.0
+        );
+
+        // Options that don't apply to a gizmo are ignored,
+        // and options left at their default aren't encoded.
+        let value = arrow(Vec3::ZERO, Vec3::ONE, RED).resolution(64);
+        assert_eq!(
+            value.to_string(),
+            super::super::arrow(Vec3::ZERO, Vec3::ONE, RED)
+        );
+        assert!(!value.to_string().contains("tip_length"));
+
+        let value = grid_2d(Isometry2d::IDENTITY, UVec2::ONE, Vec2::ONE, RED)
+            .skew_x(0.5)
+            .outer_edges_y();
+        assert!(matches!(
+            value.0,
+            GizmoCommand::Grid2d {
+                skew: Vec2 { x: 0.5, y: 0.0 },
+                outer_edges: [false, true],
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,317 @@
+//! The text format of gizmo logs.
+//!
+//! Crates that log gizmos and crates that render them can depend on
+//! different versions of bevy_gizmo_log. This module documents the format
+//! they share, so that tools outside of this crate can produce and consume
+//! gizmo logs too.
+//!
+//! # Schema
+//!
+//! A gizmo is logged as a [RON](https://github.com/ron-rs/ron) tuple struct
+//! with the format version and the [`GizmoCommand`]:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_gizmo_log::gizmo::line;
+//! # use bevy_math::Vec3;
+//! assert_eq!(
+//!     line(Vec3::ZERO, Vec3::ONE, RED),
+//!     "(v:1,gizmo:Line(start:(0.0,0.0,0.0),end:(1.0,1.0,1.0),\
+//!      color:Srgba((red:1.0,green:0.0,blue:0.0,alpha:1.0))))"
+//! );
+//! ```
+//!
+//! Vectors, isometries and colors use the serde representation of bevy_math
+//! and bevy_color. Each [`GizmoCommand`] variant is written with its name and
+//! named fields. Optional fields, such as the options set with
+//! [`GizmoValue::resolution`](super::GizmoValue::resolution), are left out
+//! when they have their default value.
+//!
+//! The `gizmo.transform` field of spans, written by
+//! [`transform`](super::transform), is the serde representation of a
+//! `Mat4`: a tuple of its 16 elements in column-major order.
+//!
+//! # Compatibility
+//!
+//! Within a version, new variants and new fields with default values may be
+//! added, but existing variants and fields are never changed or removed.
+//! Unknown fields are ignored when decoding. [`VERSION`] is increased when
+//! a change is not compatible.
+//!
+//! Decoding a gizmo with a variant that this version of the crate doesn't
+//! know about, of [`GizmoCommand`] or of any enum in it, fails with [`DecodeError::UnknownVariant`], so that consumers
+//! can report it instead of silently ignoring the gizmo. Decoding a payload
+//! whose version is newer than [`VERSION`] fails with
+//! [`DecodeError::UnsupportedVersion`], since its gizmos may not mean the
+//! same thing anymore.
+//!
+//! Payloads without a version, as logged by bevy_gizmo_log 0.3 and earlier,
+//! are decoded as version 0, which is the same as version 1 without the tuple.
+//!
+//! # Binary encoding
+//!
+//! Formatting and parsing floats makes large gizmos, such as long linestrips,
+//! slow to log and verbose in log files. With the `cbor` feature, gizmos can
+//! be encoded as `cbor:` followed by a [CBOR](https://cbor.io) array encoded
+//! in base64 with padding. The array holds the names of fields and variants,
+//! then the same payload as in RON, where each map key is replaced by its
+//! index in the names, since they repeat for every point of a linestrip.
+//! CBOR is self-describing like RON, so the compatibility rules above apply
+//! to it too.
+//!
+//! Producers choose the encoding with [`set_encoding`], or per gizmo with
+//! [`encode_with`]. [`decode`] accepts both encodings, so they can be mixed
+//! in one app. Without the `cbor` feature, decoding a binary payload fails
+//! with [`DecodeError::Binary`].
+
+use std::fmt;
+#[cfg(feature = "cbor")]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+use super::GizmoCommand;
+
+/// The version of the format written by [`encode`].
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    v: u32,
+    gizmo: &'a GizmoCommand,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    v: u32,
+    #[serde(rename = "gizmo")]
+    _gizmo: IgnoredAny,
+}
+
+#[derive(Deserialize)]
+struct OwnedPayload {
+    v: u32,
+    gizmo: GizmoCommand,
+}
+
+#[cfg(feature = "cbor")]
+mod cbor;
+
+/// The prefix of binary payloads.
+const CBOR_PREFIX: &str = "cbor:";
+
+/// Whether [`encode`] writes binary payloads.
+#[cfg(feature = "cbor")]
+static ENCODE_CBOR: AtomicBool = AtomicBool::new(false);
+
+/// An encoding of gizmo payloads.
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// RON text, the default.
+    #[default]
+    Ron,
+    /// Base64-encoded CBOR, prefixed with `cbor:`.
+    Cbor,
+}
+
+/// Set the encoding of the gizmos returned by the functions in
+/// [`gizmo`](super) and written by [`GizmoValue`](super::GizmoValue)s
+/// from now on, in the whole process.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::gizmo::{linestrip_2d, wire::{self, Encoding}};
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec2;
+/// wire::set_encoding(Encoding::Cbor);
+/// # let path = vec![Vec2::ZERO; 250];
+/// debug!(gizmo = linestrip_2d(path, RED));
+/// # wire::set_encoding(Encoding::Ron);
+/// ```
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub fn set_encoding(encoding: Encoding) {
+    ENCODE_CBOR.store(encoding == Encoding::Cbor, Ordering::Relaxed);
+}
+
+/// Encode a gizmo in the current version of the format, in the encoding set
+/// with `set_encoding`, which is RON by default.
+pub fn encode(gizmo: &GizmoCommand) -> String {
+    #[cfg(feature = "cbor")]
+    if ENCODE_CBOR.load(Ordering::Relaxed) {
+        return encode_with(gizmo, Encoding::Cbor);
+    }
+    ron::ser::to_string(&Payload { v: VERSION, gizmo }).unwrap()
+}
+
+/// Encode a gizmo in the current version of the format, in `encoding`.
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub fn encode_with(gizmo: &GizmoCommand, encoding: Encoding) -> String {
+    let payload = Payload { v: VERSION, gizmo };
+    match encoding {
+        Encoding::Ron => ron::ser::to_string(&payload).unwrap(),
+        Encoding::Cbor => cbor::encode(&payload),
+    }
+}
+
+/// Decode a gizmo that was encoded in any version of the format, in any encoding.
+pub fn decode(payload: &str) -> Result<GizmoCommand, DecodeError> {
+    if let Some(binary) = payload.strip_prefix(CBOR_PREFIX) {
+        #[cfg(feature = "cbor")]
+        return cbor::decode(binary);
+        #[cfg(not(feature = "cbor"))]
+        return Err(DecodeError::Binary(format!(
+            "binary gizmos need the `cbor` feature of bevy_gizmo_log: {binary}"
+        )));
+    }
+    // Versioned payloads are tuples, legacy payloads start with the variant name.
+    if !payload.trim_start().starts_with('(') {
+        return ron::de::from_str(payload).map_err(|err| DecodeError::from_ron(err, 0));
+    }
+    match ron::de::from_str::<OwnedPayload>(payload) {
+        Ok(payload) => {
+            check_version(payload.v)?;
+            Ok(payload.gizmo)
+        }
+        // Only the header is parsed again, to report the version of the payload.
+        Err(err) => match ron::de::from_str::<Header>(payload) {
+            Ok(header) => {
+                check_version(header.v)?;
+                Err(DecodeError::from_ron(err, header.v))
+            }
+            Err(_) => Err(DecodeError::Parse(err)),
+        },
+    }
+}
+
+/// Fail if `version` is newer than the versions this crate can decode.
+fn check_version(version: u32) -> Result<(), DecodeError> {
+    if version > VERSION {
+        return Err(DecodeError::UnsupportedVersion { version });
+    }
+    Ok(())
+}
+
+/// Error returned by [`decode`].
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The gizmo is a variant that this version of bevy_gizmo_log doesn't know,
+    /// probably because it was logged by a newer version.
+    UnknownVariant {
+        /// The name of the variant.
+        variant: String,
+        /// The format version of the payload.
+        version: u32,
+    },
+    /// The payload is in a newer version of the format than this version of
+    /// bevy_gizmo_log supports.
+    UnsupportedVersion {
+        /// The format version of the payload.
+        version: u32,
+    },
+    /// The payload is not a valid gizmo.
+    Parse(ron::error::SpannedError),
+    /// The payload is a binary gizmo that isn't valid, or that can't be
+    /// decoded because the `cbor` feature is disabled.
+    Binary(String),
+}
+
+impl DecodeError {
+    fn from_ron(err: ron::error::SpannedError, version: u32) -> Self {
+        match err.code {
+            // Any enum in a gizmo, such as the shape of a primitive, may gain variants.
+            ron::Error::NoSuchEnumVariant { ref found, .. } => DecodeError::UnknownVariant {
+                variant: found.clone(),
+                version,
+            },
+            _ => DecodeError::Parse(err),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownVariant { variant, version } => write!(
+                f,
+                "unknown gizmo `{variant}` in format version {version}, \
+                 it was probably logged by a newer version of bevy_gizmo_log"
+            ),
+            DecodeError::UnsupportedVersion { version } => write!(
+                f,
+                "gizmo in format version {version}, \
+                 this version of bevy_gizmo_log supports up to version {VERSION}"
+            ),
+            DecodeError::Parse(err) => write!(f, "could not parse gizmo: {err}"),
+            DecodeError::Binary(err) => write!(f, "could not decode binary gizmo: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::Vec3;
+
+    use super::*;
+    use crate::gizmo::arrow;
+
+    #[test]
+    fn test_round_trip() {
+        let payload = arrow(Vec3::ZERO, Vec3::ONE, RED);
+        assert!(payload.starts_with("(v:1,gizmo:Arrow("));
+        let command = decode(&payload).unwrap();
+        assert_eq!(encode(&command), payload);
+    }
+
+    #[test]
+    fn test_legacy_payload() {
+        let command = decode(&arrow(Vec3::ZERO, Vec3::ONE, RED)).unwrap();
+        let legacy = ron::ser::to_string(&command).unwrap();
+        assert!(legacy.starts_with("Arrow("));
+        assert_eq!(decode(&legacy).unwrap(), command);
+    }
+
+    #[test]
+    fn test_forward_compatibility() {
+        // Unknown fields are ignored.
+        let payload =
+            "(v:1,gizmo:Line(start:(0,0,0),end:(1,1,1),color:LinearRgba((red:1,green:0,blue:0,alpha:1)),width:2))";
+        assert!(decode(payload).is_ok());
+
+        // Unknown variants are reported.
+        let payload = "(v:1,gizmo:Spiral(turns:3))";
+        match decode(payload) {
+            Err(DecodeError::UnknownVariant { variant, version }) => {
+                assert_eq!(variant, "Spiral");
+                assert_eq!(version, 1);
+            }
+            other => panic!("expected an unknown variant, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_nested_variant() {
+        let payload = "(v:1,gizmo:Batch(gizmos:[Primitive3d(primitive:Pyramid((height:1)),\
+            isometry:(rotation:(0,0,0,1),translation:(0,0,0)),\
+            color:LinearRgba((red:1,green:0,blue:0,alpha:1)))]))";
+        match decode(payload) {
+            Err(DecodeError::UnknownVariant { variant, .. }) => assert_eq!(variant, "Pyramid"),
+            other => panic!("expected an unknown variant, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_newer_version() {
+        let payload = "(v:2,gizmo:Line(start:(0,0,0),end:(1,1,1),color:LinearRgba((red:1,green:0,blue:0,alpha:1))))";
+        match decode(payload) {
+            Err(DecodeError::UnsupportedVersion { version }) => assert_eq!(version, 2),
+            other => panic!("expected an unsupported version, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! The binary encoding of gizmo payloads, enabled by the `cbor` feature.
+
+use std::{collections::HashMap, fmt};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ciborium::Value;
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer, StrDeserializer},
+        DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+use super::{check_version, DecodeError, GizmoCommand, Header, OwnedPayload, Payload, CBOR_PREFIX};
+
+pub(super) fn encode(payload: &Payload) -> String {
+    let mut value = Value::serialized(payload).unwrap();
+    let mut names = Names::default();
+    names.intern(&mut value);
+    let mut bytes = Vec::new();
+    ciborium::into_writer(
+        &Value::Array(vec![Value::Array(names.list), value]),
+        &mut bytes,
+    )
+    .unwrap();
+    let mut encoded = CBOR_PREFIX.to_owned();
+    STANDARD.encode_string(bytes, &mut encoded);
+    encoded
+}
+
+/// Decode a payload without its prefix.
+pub(super) fn decode(payload: &str) -> Result<GizmoCommand, DecodeError> {
+    let bytes = STANDARD.decode(payload.trim_end()).map_err(binary_error)?;
+    let value: Value = ciborium::from_reader(&bytes[..]).map_err(binary_error)?;
+    let invalid = || binary_error("expected an array of names and a payload");
+    let Value::Array(array) = value else {
+        return Err(invalid());
+    };
+    let Ok([Value::Array(names), mut value]) = <[Value; 2]>::try_from(array) else {
+        return Err(invalid());
+    };
+    expand(&mut value, &names)?;
+
+    match OwnedPayload::deserialize(ValueDeserializer(&value)) {
+        Ok(payload) => {
+            check_version(payload.v)?;
+            Ok(payload.gizmo)
+        }
+        // Only the header is deserialized again, to report the version of the payload.
+        Err(err) => {
+            let version = Header::deserialize(ValueDeserializer(&value))
+                .map_err(|_| binary_error(&err))?
+                .v;
+            check_version(version)?;
+            match err {
+                ValueError::UnknownVariant(variant) => {
+                    Err(DecodeError::UnknownVariant { variant, version })
+                }
+                ValueError::Custom(_) => Err(binary_error(err)),
+            }
+        }
+    }
+}
+
+fn binary_error(err: impl fmt::Display) -> DecodeError {
+    DecodeError::Binary(err.to_string())
+}
+
+/// The names of fields and variants in a payload, in order of appearance.
+#[derive(Default)]
+struct Names {
+    list: Vec<Value>,
+    indices: HashMap<String, usize>,
+}
+
+impl Names {
+    /// Replace the names that are map keys in `value` by their index.
+    fn intern(&mut self, value: &mut Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.intern(item)),
+            Value::Map(entries) => {
+                for (key, value) in entries {
+                    if let Value::Text(name) = key {
+                        let index =
+                            *self
+                                .indices
+                                .entry(std::mem::take(name))
+                                .or_insert_with_key(|name| {
+                                    self.list.push(Value::Text(name.clone()));
+                                    self.list.len() - 1
+                                });
+                        *key = Value::from(index as u64);
+                    }
+                    self.intern(value);
+                }
+            }
+            Value::Tag(_, value) => self.intern(value),
+            _ => {}
+        }
+    }
+}
+
+/// Replace the indices that are map keys in `value` by their name.
+fn expand(value: &mut Value, names: &[Value]) -> Result<(), DecodeError> {
+    match value {
+        Value::Array(items) => items.iter_mut().try_for_each(|item| expand(item, names)),
+        Value::Map(entries) => entries.iter_mut().try_for_each(|(key, value)| {
+            if let Value::Integer(index) = key {
+                let name = usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| names.get(index))
+                    .ok_or_else(|| binary_error("name index out of range"))?;
+                *key = name.clone();
+            }
+            expand(value, names)
+        }),
+        Value::Tag(_, value) => expand(value, names),
+        _ => Ok(()),
+    }
+}
+
+/// Deserializer of an expanded payload.
+///
+/// Unlike the deserializer of [`Value`], its errors tell unknown variants
+/// apart from other errors, at any depth of the gizmo.
+struct ValueDeserializer<'a>(&'a Value);
+
+#[derive(Debug)]
+enum ValueError {
+    UnknownVariant(String),
+    Custom(String),
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueError::UnknownVariant(variant) => write!(f, "unknown variant `{variant}`"),
+            ValueError::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError::Custom(msg.to_string())
+    }
+
+    fn unknown_variant(variant: &str, _expected: &'static [&'static str]) -> Self {
+        ValueError::UnknownVariant(variant.to_owned())
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for ValueDeserializer<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self.0 {
+            Value::Integer(integer) => match i64::try_from(*integer) {
+                Ok(integer) => visitor.visit_i64(integer),
+                Err(_) => visitor.visit_i128(i128::from(*integer)),
+            },
+            Value::Bytes(bytes) => visitor.visit_bytes(bytes),
+            Value::Float(float) => visitor.visit_f64(*float),
+            Value::Text(text) => visitor.visit_str(text),
+            Value::Bool(bool) => visitor.visit_bool(*bool),
+            Value::Null => visitor.visit_unit(),
+            Value::Tag(_, value) => ValueDeserializer(value).deserialize_any(visitor),
+            Value::Array(items) => {
+                let mut seq = SeqDeserializer::new(items.iter().map(ValueDeserializer));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(entries) => {
+                let mut map = MapDeserializer::new(
+                    entries
+                        .iter()
+                        .map(|(key, value)| (ValueDeserializer(key), ValueDeserializer(value))),
+                );
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            _ => Err(de::Error::custom("unsupported CBOR value")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Variants are written as their name, or as a map from their name to their fields.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self.0 {
+            Value::Text(variant) => visitor.visit_enum(StrDeserializer::new(variant)),
+            Value::Map(entries) => match &entries[..] {
+                [(variant, value)] => visitor.visit_enum(ValueEnum { variant, value }),
+                _ => Err(de::Error::custom("expected a map with a single variant")),
+            },
+            _ => Err(de::Error::custom("expected a variant")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// A variant with fields, written as a map from its name to its fields.
+struct ValueEnum<'a> {
+    variant: &'a Value,
+    value: &'a Value,
+}
+
+impl<'de, 'a> EnumAccess<'de> for ValueEnum<'a> {
+    type Error = ValueError;
+    type Variant = ValueDeserializer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, ValueDeserializer<'a>), ValueError> {
+        let variant = seed.deserialize(ValueDeserializer(self.variant))?;
+        Ok((variant, ValueDeserializer(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for ValueDeserializer<'_> {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, ValueError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        self.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        self.deserialize_any(visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::{palettes::css::RED, Color};
+    use bevy_math::{
+        primitives::{Circle, Cuboid, Sphere},
+        Isometry3d, Mat4, Vec2, Vec3,
+    };
+
+    use super::*;
+    use crate::gizmo::{
+        typed::{arrow_2d, primitive_2d, primitive_3d},
+        wire::{self, Encoding},
+        GizmoBatch, GizmoValue,
+    };
+
+    /// A batch with nested enums: colors, shapes, options and a transform.
+    fn nested() -> GizmoCommand {
+        let mut batch = GizmoBatch::new();
+        batch
+            .add(primitive_3d(Sphere::new(1.0), Isometry3d::IDENTITY, RED))
+            .add(primitive_3d(Cuboid::new(1.0, 2.0, 3.0), Vec3::X, RED))
+            .add(primitive_2d(Circle::new(1.0), Vec2::Y, Color::WHITE))
+            .add(arrow_2d(Vec2::ZERO, Vec2::X, RED).with_double_end());
+        GizmoCommand::Transformed {
+            transform: Mat4::from_translation(Vec3::Z),
+            gizmo: Box::new(GizmoValue::from(batch).0),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let positions = (0..250)
+            .map(|i| {
+                let x = i as f32 * 0.37;
+                (Vec2::new(x, x.sin()), Color::hsl(x * 10., 0.5, 0.5))
+            })
+            .collect();
+        let command = GizmoCommand::LinestripGradient2d { positions };
+        let cbor = wire::encode_with(&command, Encoding::Cbor);
+        let ron = wire::encode_with(&command, Encoding::Ron);
+        assert!(cbor.starts_with(CBOR_PREFIX));
+        assert!(
+            cbor.len() < ron.len() * 2 / 3,
+            "{} >= {}",
+            cbor.len(),
+            ron.len()
+        );
+        assert_eq!(wire::decode(&cbor).unwrap(), command);
+        assert_eq!(wire::decode(&ron).unwrap(), command);
+
+        let command = nested();
+        assert_eq!(
+            wire::decode(&wire::encode_with(&command, Encoding::Cbor)).unwrap(),
+            command
+        );
+    }
+
+    /// A binary payload of a gizmo that this version doesn't know.
+    fn spiral(version: u32) -> String {
+        let text = |text: &str| Value::Text(text.to_owned());
+        let names = vec![text("v"), text("gizmo"), text("Spiral"), text("turns")];
+        let payload = Value::Map(vec![
+            (Value::from(0), Value::from(version)),
+            (
+                Value::from(1),
+                Value::Map(vec![(
+                    Value::from(2),
+                    Value::Map(vec![(Value::from(3), Value::from(3))]),
+                )]),
+            ),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::into_writer(
+            &Value::Array(vec![Value::Array(names), payload]),
+            &mut bytes,
+        )
+        .unwrap();
+        format!("{CBOR_PREFIX}{}", STANDARD.encode(bytes))
+    }
+
+    #[test]
+    fn test_unknown_variant() {
+        match wire::decode(&spiral(1)) {
+            Err(DecodeError::UnknownVariant { variant, version }) => {
+                assert_eq!((variant.as_str(), version), ("Spiral", 1));
+            }
+            other => panic!("expected an unknown variant, got {other:?}"),
+        }
+        match wire::decode(&spiral(2)) {
+            Err(DecodeError::UnsupportedVersion { version }) => assert_eq!(version, 2),
+            other => panic!("expected an unsupported version, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_nested_variant() {
+        // A newer shape, in place of the sphere.
+        let cbor = wire::encode_with(&nested(), Encoding::Cbor);
+        let bytes = STANDARD.decode(&cbor[CBOR_PREFIX.len()..]).unwrap();
+        let Value::Array(mut array) = ciborium::from_reader(&bytes[..]).unwrap() else {
+            unreachable!();
+        };
+        let Value::Array(names) = &mut array[0] else {
+            unreachable!();
+        };
+        for name in names {
+            if name.as_text() == Some("Sphere") {
+                *name = Value::Text("Pyramid".to_owned());
+            }
+        }
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Value::Array(array), &mut bytes).unwrap();
+        match wire::decode(&format!("{CBOR_PREFIX}{}", STANDARD.encode(bytes))) {
+            Err(DecodeError::UnknownVariant { variant, .. }) => assert_eq!(variant, "Pyramid"),
+            other => panic!("expected an unknown variant, got {other:?}"),
+        }
+    }
+}
@@ -4,7 +4,7 @@ use bevy_color::Color;
 use bevy_math::{Isometry2d, Isometry3d, Quat, Vec2, Vec3};
 use nalgebra::{Isometry2, Isometry3, Matrix4, Vector2, Vector3};
 
-use crate::gizmo;
+use crate::gizmo::{self, Shape2d, Shape3d};
 
 /// Gizmo log version of [`arc_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.arc_2d).
 pub fn arc_2d(
@@ -56,6 +56,30 @@ pub fn circle_2d(position: Vector2<f32>, radius: f32, color: impl Into<Color>) -
     gizmo::circle_2d(position.into(), radius, color)
 }
 
+/// nalgebra version of [`gizmo::cubic_bezier`].
+pub fn cubic_bezier(
+    control_points: impl IntoIterator<Item = [Vector3<f32>; 4]>,
+    color: impl Into<Color>,
+) -> String {
+    let control_points: Vec<_> = control_points
+        .into_iter()
+        .map(|points| points.map(|v| v.into()))
+        .collect();
+    gizmo::cubic_bezier(control_points, color)
+}
+
+/// nalgebra version of [`gizmo::cubic_bezier_2d`].
+pub fn cubic_bezier_2d(
+    control_points: impl IntoIterator<Item = [Vector2<f32>; 4]>,
+    color: impl Into<Color>,
+) -> String {
+    let control_points: Vec<_> = control_points
+        .into_iter()
+        .map(|points| points.map(|v| v.into()))
+        .collect();
+    gizmo::cubic_bezier_2d(control_points, color)
+}
+
 /// Gizmo log version of [`cuboid`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.cuboid).
 pub fn cuboid(transform: Matrix4<f32>, color: impl Into<Color>) -> String {
     gizmo::cuboid(bevy_math::Mat4::from(transform), color)
@@ -124,6 +148,11 @@ pub fn grid_3d(
     )
 }
 
+/// Nalgebra version of [`gizmo::label`].
+pub fn label(position: Vector3<f32>, text: impl Into<String>, color: impl Into<Color>) -> String {
+    gizmo::label(position.into(), text, color)
+}
+
 /// Gizmo log version of [`line`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.line).
 pub fn line(start: Vector3<f32>, end: Vector3<f32>, color: impl Into<Color>) -> String {
     gizmo::line(start.into(), end.into(), color)
@@ -194,6 +223,24 @@ pub fn long_arc_3d_between(
     gizmo::long_arc_3d_between(center.into(), from.into(), to.into(), color)
 }
 
+/// Gizmo log version of [`primitive_2d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/primitives/dim2/trait.GizmoPrimitive2d.html#tymethod.primitive_2d).
+pub fn primitive_2d(
+    primitive: impl Into<Shape2d>,
+    isometry: Isometry2<f32>,
+    color: impl Into<Color>,
+) -> String {
+    gizmo::primitive_2d(primitive, convert_2d(isometry), color)
+}
+
+/// Gizmo log version of [`primitive_3d`](https://docs.rs/bevy/0.15.0/bevy/gizmos/primitives/dim3/trait.GizmoPrimitive3d.html#tymethod.primitive_3d).
+pub fn primitive_3d(
+    primitive: impl Into<Shape3d>,
+    isometry: Isometry3<f32>,
+    color: impl Into<Color>,
+) -> String {
+    gizmo::primitive_3d(primitive, convert_3d(isometry), color)
+}
+
 /// Gizmo log version of [`ray`](https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html#method.ray).
 pub fn ray(start: Vector3<f32>, vector: Vector3<f32>, color: impl Into<Color>) -> String {
     gizmo::ray(start.into(), vector.into(), color)
@@ -276,6 +323,11 @@ pub fn sphere(isometry: Isometry3<f32>, radius: f32, color: impl Into<Color>) ->
     gizmo::sphere(convert_3d(isometry), radius, color)
 }
 
+/// Nalgebra version of [`gizmo::transform`], for `gizmo.transform` span fields.
+pub fn transform(transform: Matrix4<f32>) -> String {
+    gizmo::transform(bevy_math::Mat4::from(transform))
+}
+
 fn convert_2d(isometry: Isometry2<f32>) -> Isometry2d {
     let (translation, rotation_angle): (Vec2, f32) = isometry.into();
     Isometry2d::new(translation, rotation_angle.into())
@@ -310,20 +362,18 @@ mod tests {
             let na_str = ellipse_2d(na_isometry, Vector2::new(1., 2.), color);
             let glam_str = gizmo::ellipse_2d(glam_isometry, Vec2::new(1., 2.), color);
 
-            let na_command: GizmoCommand = ron::de::from_str(&na_str).unwrap();
-            let glam_command: GizmoCommand = ron::de::from_str(&glam_str).unwrap();
+            let na_command: GizmoCommand = crate::gizmo::wire::decode(&na_str).unwrap();
+            let glam_command: GizmoCommand = crate::gizmo::wire::decode(&glam_str).unwrap();
 
             match (na_command, glam_command) {
                 (
                     GizmoCommand::Ellipse2d {
                         isometry: isometry1,
-                        half_size: _,
-                        color: _,
+                        ..
                     },
                     GizmoCommand::Ellipse2d {
                         isometry: isometry2,
-                        half_size: _,
-                        color: _,
+                        ..
                     },
                 ) => {
                     assert_relative_eq!(
@@ -362,20 +412,18 @@ mod tests {
             let na_str = ellipse(na_isometry, Vector2::new(1., 2.), color);
             let glam_str = gizmo::ellipse(glam_isometry, Vec2::new(1., 2.), color);
 
-            let na_command: GizmoCommand = ron::de::from_str(&na_str).unwrap();
-            let glam_command: GizmoCommand = ron::de::from_str(&glam_str).unwrap();
+            let na_command: GizmoCommand = crate::gizmo::wire::decode(&na_str).unwrap();
+            let glam_command: GizmoCommand = crate::gizmo::wire::decode(&glam_str).unwrap();
 
             match (na_command, glam_command) {
                 (
                     GizmoCommand::Ellipse {
                         isometry: isometry1,
-                        half_size: _,
-                        color: _,
+                        ..
                     },
                     GizmoCommand::Ellipse {
                         isometry: isometry2,
-                        half_size: _,
-                        color: _,
+                        ..
                     },
                 ) => {
                     assert_relative_eq!(
@@ -0,0 +1,327 @@
+use std::{any::TypeId, collections::HashMap};
+
+use bevy_app::App;
+use bevy_ecs::{
+    schedule::{InternedScheduleLabel, IntoSystemConfigs},
+    system::{ResMut, Resource},
+};
+use bevy_gizmos::{config::GizmoConfigGroup, gizmos::Gizmos, AppGizmoBuilder};
+use bevy_reflect::Reflect;
+use tracing::Level;
+
+use crate::{gizmo::GizmoCommand, render_gizmo_log_events};
+#[cfg(feature = "labels")]
+use crate::{
+    inspector::InspectedGizmo,
+    labels::{self, QueuedLabel},
+    retained::PlacedGizmo,
+};
+
+/// The [`GizmoConfigGroup`] that logged gizmos are drawn in.
+///
+/// Use it to change the line width, depth bias or render layers of logged
+/// gizmos, or to toggle them separately from other gizmos:
+///
+/// ```
+/// # use bevy_ecs::system::ResMut;
+/// # use bevy_gizmo_log::LogGizmoConfigGroup;
+/// # use bevy_gizmos::config::GizmoConfigStore;
+/// fn toggle_logged_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+///     let (config, _) = config_store.config_mut::<LogGizmoConfigGroup>();
+///     config.enabled = !config.enabled;
+/// }
+/// ```
+///
+/// Gizmos can be drawn in other groups with [`AppGizmoLogBuilder`].
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct LogGizmoConfigGroup;
+
+/// Extension trait that routes gizmo logs to [`GizmoConfigGroup`]s.
+///
+/// Gizmos are drawn in the group registered for their `gizmo.group` field
+/// if there is one, otherwise in the group registered for the most specific
+/// prefix of their target, otherwise in [`LogGizmoConfigGroup`].
+///
+/// ```no_run
+/// # use bevy::DefaultPlugins;
+/// # use bevy_app::{App, PluginGroup};
+/// # use bevy_gizmo_log::{AppGizmoLogBuilder, GizmoLogPlugin};
+/// # use bevy_gizmos::config::GizmoConfigGroup;
+/// # use bevy_log::LogPlugin;
+/// # use bevy_reflect::Reflect;
+/// #[derive(Default, Reflect, GizmoConfigGroup)]
+/// struct PhysicsGizmos;
+///
+/// App::new()
+///     .add_plugins(DefaultPlugins.build().disable::<LogPlugin>())
+///     .add_plugins(GizmoLogPlugin::default())
+///     // debug!(gizmo = ..., gizmo.group = "physics")
+///     .route_gizmo_log_group::<PhysicsGizmos>("physics")
+///     // debug!(target: "my_game::physics::contacts", gizmo = ...)
+///     .route_gizmo_log_target::<PhysicsGizmos>("my_game::physics");
+/// ```
+///
+/// Routes can only be added after [`GizmoLogPlugin`] or [`GizmoLayer`].
+/// The group is registered with [`init_gizmo_group`] if it wasn't already.
+///
+/// [`GizmoLogPlugin`]: crate::GizmoLogPlugin
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`init_gizmo_group`]: AppGizmoBuilder::init_gizmo_group
+pub trait AppGizmoLogBuilder {
+    /// Draw gizmos logged with `gizmo.group = name` in `Config`.
+    fn route_gizmo_log_group<Config: GizmoConfigGroup>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self;
+
+    /// Draw gizmos logged with `target`, or with a target nested in it, in `Config`.
+    fn route_gizmo_log_target<Config: GizmoConfigGroup>(
+        &mut self,
+        target: impl Into<String>,
+    ) -> &mut Self;
+}
+
+impl AppGizmoLogBuilder for App {
+    fn route_gizmo_log_group<Config: GizmoConfigGroup>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut Self {
+        let group = register_group::<Config>(self);
+        let mut routes = self.world_mut().resource_mut::<GizmoLogRoutes>();
+        routes.names.insert(name.into(), group);
+        self
+    }
+
+    fn route_gizmo_log_target<Config: GizmoConfigGroup>(
+        &mut self,
+        target: impl Into<String>,
+    ) -> &mut Self {
+        let group = register_group::<Config>(self);
+        let mut routes = self.world_mut().resource_mut::<GizmoLogRoutes>();
+        routes.targets.push((target.into(), group));
+        self
+    }
+}
+
+/// Bevy resource that decides which config group each gizmo log is drawn in.
+///
+/// Groups other than [`LogGizmoConfigGroup`] are identified by their index
+/// in `queued`. Their gizmos are queued by [`render_gizmo_log_events`] and
+/// drawn by a [`draw_gizmo_log_group`] system per group.
+#[derive(Resource)]
+pub struct GizmoLogRoutes {
+    /// The schedule of [`render_gizmo_log_events`].
+    schedule: InternedScheduleLabel,
+    groups: HashMap<TypeId, usize>,
+    names: HashMap<String, usize>,
+    targets: Vec<(String, usize)>,
+    /// The config group of each level, used by [`GizmoLevelStyles`].
+    ///
+    /// [`GizmoLevelStyles`]: crate::GizmoLevelStyles
+    levels: HashMap<Level, usize>,
+    queued: Vec<Vec<GizmoCommand>>,
+    /// Labels to draw this frame, in any config group.
+    #[cfg(feature = "labels")]
+    labels: Vec<QueuedLabel>,
+    /// Gizmos drawn this frame, collected while [`GizmoInspector`] is enabled.
+    ///
+    /// [`GizmoInspector`]: crate::GizmoInspector
+    #[cfg(feature = "labels")]
+    inspected: Option<Vec<InspectedGizmo>>,
+}
+
+impl GizmoLogRoutes {
+    pub(crate) fn new(schedule: InternedScheduleLabel) -> Self {
+        Self {
+            schedule,
+            groups: HashMap::new(),
+            names: HashMap::new(),
+            targets: Vec::new(),
+            levels: HashMap::new(),
+            queued: Vec::new(),
+            #[cfg(feature = "labels")]
+            labels: Vec::new(),
+            #[cfg(feature = "labels")]
+            inspected: None,
+        }
+    }
+
+    pub(crate) fn schedule(&self) -> InternedScheduleLabel {
+        self.schedule
+    }
+
+    /// The config group of gizmos logged at `level` whose style overrides the config.
+    pub(crate) fn level_group(&self, level: Level) -> usize {
+        self.levels[&level]
+    }
+
+    /// The group of a gizmo, or `None` for [`LogGizmoConfigGroup`].
+    pub(crate) fn resolve(&self, name: Option<&str>, target: &str) -> Option<usize> {
+        if let Some(&group) = name.and_then(|name| self.names.get(name)) {
+            return Some(group);
+        }
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, group)| group)
+    }
+
+    /// Draw the labels in `command`, and `message` next to it, this frame.
+    #[cfg(feature = "labels")]
+    pub(crate) fn queue_labels(&mut self, command: &GizmoCommand, message: Option<&str>) {
+        labels::queue_labels(command, message, &mut self.labels);
+    }
+
+    #[cfg(feature = "labels")]
+    pub(crate) fn take_labels(&mut self) -> Vec<QueuedLabel> {
+        std::mem::take(&mut self.labels)
+    }
+
+    /// Remember that `gizmo` was drawn as `command` this frame, if it is being inspected.
+    #[cfg(feature = "labels")]
+    pub(crate) fn inspect(&mut self, command: &GizmoCommand, gizmo: &PlacedGizmo) {
+        if let Some(inspected) = &mut self.inspected {
+            inspected.push(InspectedGizmo {
+                command: command.clone(),
+                source: gizmo.source.clone(),
+                message: gizmo.message.clone(),
+            });
+        }
+    }
+
+    /// The gizmos drawn since the last call, and whether to collect them until the next.
+    #[cfg(feature = "labels")]
+    pub(crate) fn take_inspected(&mut self, enabled: bool) -> Vec<InspectedGizmo> {
+        let inspected = self.inspected.take().unwrap_or_default();
+        if enabled {
+            self.inspected = Some(Vec::new());
+        }
+        inspected
+    }
+
+    /// Draw `command` in `group` this frame.
+    pub(crate) fn draw(
+        &mut self,
+        gizmos: &mut Gizmos<LogGizmoConfigGroup>,
+        command: &GizmoCommand,
+        group: Option<usize>,
+    ) {
+        match group {
+            Some(group) => self.queued[group].push(command.clone()),
+            None => command.draw(gizmos),
+        }
+    }
+}
+
+/// Draw gizmos logged at `level` in `Config` if their style overrides the config.
+pub(crate) fn route_level<Config: GizmoConfigGroup>(app: &mut App, level: Level) {
+    let group = register_group::<Config>(app);
+    let mut routes = app.world_mut().resource_mut::<GizmoLogRoutes>();
+    routes.levels.insert(level, group);
+}
+
+fn register_group<Config: GizmoConfigGroup>(app: &mut App) -> usize {
+    app.init_gizmo_group::<Config>();
+    let mut routes = app.world_mut().get_resource_mut::<GizmoLogRoutes>().expect(
+        "GizmoLogPlugin or GizmoLayer must be added before routing gizmo logs to config groups",
+    );
+    if let Some(&group) = routes.groups.get(&TypeId::of::<Config>()) {
+        return group;
+    }
+    let group = routes.queued.len();
+    routes.queued.push(Vec::new());
+    routes.groups.insert(TypeId::of::<Config>(), group);
+    let schedule = routes.schedule;
+    app.add_systems(
+        schedule,
+        draw_gizmo_log_group::<Config>.after(render_gizmo_log_events),
+    );
+    group
+}
+
+/// Bevy system that draws the gizmos queued for `Config`.
+fn draw_gizmo_log_group<Config: GizmoConfigGroup>(
+    mut routes: ResMut<GizmoLogRoutes>,
+    mut gizmos: Gizmos<Config>,
+) {
+    let group = routes.groups[&TypeId::of::<Config>()];
+    for command in routes.queued[group].drain(..) {
+        command.draw(&mut gizmos);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::Update;
+    use bevy_color::palettes::css::RED;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec3;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{gizmo::line, GizmoLayer};
+
+    #[derive(Default, Reflect, GizmoConfigGroup)]
+    struct PhysicsGizmos;
+
+    #[derive(Default, Reflect, GizmoConfigGroup)]
+    struct ContactGizmos;
+
+    #[test]
+    fn test_resolve() {
+        let mut app = App::new();
+        let _layer = GizmoLayer::new(&mut app, Update);
+        app.route_gizmo_log_group::<PhysicsGizmos>("physics")
+            .route_gizmo_log_target::<PhysicsGizmos>("game::physics")
+            .route_gizmo_log_target::<ContactGizmos>("game::physics::contacts");
+
+        let routes = app.world().resource::<GizmoLogRoutes>();
+        let physics = routes.groups[&TypeId::of::<PhysicsGizmos>()];
+        let contacts = routes.groups[&TypeId::of::<ContactGizmos>()];
+        assert_eq!(routes.resolve(Some("physics"), "game"), Some(physics));
+        assert_eq!(routes.resolve(None, "game::physics"), Some(physics));
+        assert_eq!(routes.resolve(None, "game::physics::joints"), Some(physics));
+        assert_eq!(
+            routes.resolve(None, "game::physics::contacts::solver"),
+            Some(contacts)
+        );
+        assert_eq!(routes.resolve(None, "game::physics_extra"), None);
+        assert_eq!(routes.resolve(Some("unknown"), "game"), None);
+    }
+
+    #[test]
+    fn test_queue_for_group() {
+        let mut app = App::new();
+        let layer = GizmoLayer::new(&mut app, Update);
+        app.route_gizmo_log_group::<PhysicsGizmos>("physics");
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(
+                gizmo = line(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.group = "physics"
+            );
+            tracing::debug!(
+                gizmo = line(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.ttl_frames = 2
+            );
+        });
+        app.world_mut()
+            .run_system_once(render_gizmo_log_events)
+            .unwrap();
+        let queued = |app: &App| {
+            let routes = app.world().resource::<GizmoLogRoutes>();
+            routes.queued[routes.groups[&TypeId::of::<PhysicsGizmos>()]].len()
+        };
+        assert_eq!(queued(&app), 1);
+
+        app.world_mut()
+            .run_system_once(draw_gizmo_log_group::<PhysicsGizmos>)
+            .unwrap();
+        assert_eq!(queued(&app), 0);
+    }
+}
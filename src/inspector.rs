@@ -0,0 +1,282 @@
+use bevy_app::{App, Plugin};
+use bevy_color::{Alpha, Color};
+use bevy_ecs::{
+    bundle::Bundle,
+    change_detection::DetectChangesMut,
+    component::Component,
+    query::With,
+    schedule::IntoSystemConfigs,
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy_gizmos::gizmos::Gizmos;
+use bevy_math::{Vec2, Vec3};
+use bevy_render::{camera::Camera, view::Visibility};
+use bevy_text::TextFont;
+use bevy_transform::components::GlobalTransform;
+use bevy_ui::{widget::Text, BackgroundColor, GlobalZIndex, Node, PositionType, UiRect, Val};
+use bevy_window::{PrimaryWindow, Window};
+
+use crate::{
+    gizmo::GizmoCommand,
+    groups::{GizmoLogRoutes, LogGizmoConfigGroup},
+    render_gizmo_log_events,
+    retained::GizmoSource,
+    space::active_camera,
+    tessellate::Lines,
+};
+
+/// Distance in logical pixels between the cursor and the tooltip.
+const TOOLTIP_OFFSET: f32 = 16.0;
+
+/// Plugin that shows where a logged gizmo comes from when the cursor hovers
+/// over it.
+///
+/// The hovered gizmo is highlighted, and a tooltip shows the level, target,
+/// file, line and spans of the log event that drew it, followed by its
+/// message. The tooltip is drawn on the active camera with the highest order,
+/// at the cursor of the primary window.
+///
+/// ```no_run
+/// # use bevy::DefaultPlugins;
+/// # use bevy_app::{App, PluginGroup};
+/// # use bevy_gizmo_log::{GizmoInspectorPlugin, GizmoLogPlugin};
+/// # use bevy_log::LogPlugin;
+/// App::new()
+///     .add_plugins(DefaultPlugins.build().disable::<LogPlugin>())
+///     .add_plugins((GizmoLogPlugin::default(), GizmoInspectorPlugin));
+/// ```
+///
+/// Inspecting can be toggled with the [`GizmoInspector`] resource.
+///
+/// [`GizmoInspectorPlugin`] must be added after [`GizmoLogPlugin`] or [`GizmoLayer`].
+///
+/// [`GizmoLogPlugin`]: crate::GizmoLogPlugin
+/// [`GizmoLayer`]: crate::GizmoLayer
+pub struct GizmoInspectorPlugin;
+
+impl Plugin for GizmoInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = app
+            .world()
+            .get_resource::<GizmoLogRoutes>()
+            .expect("GizmoLogPlugin or GizmoLayer must be added before GizmoInspectorPlugin")
+            .schedule();
+        app.init_resource::<GizmoInspector>()
+            .add_systems(schedule, inspect_gizmo_logs.after(render_gizmo_log_events));
+    }
+}
+
+/// Bevy resource that configures [`GizmoInspectorPlugin`].
+#[derive(Resource, Clone, Debug)]
+pub struct GizmoInspector {
+    /// If false, gizmos aren't inspected and no tooltip is shown.
+    pub enabled: bool,
+    /// How close the cursor must be to a gizmo to inspect it, in logical pixels.
+    pub pick_distance: f32,
+}
+
+impl Default for GizmoInspector {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pick_distance: 8.0,
+        }
+    }
+}
+
+/// A gizmo drawn this frame, in world space, with the log event that drew it.
+pub(crate) struct InspectedGizmo {
+    pub(crate) command: GizmoCommand,
+    pub(crate) source: Option<GizmoSource>,
+    pub(crate) message: Option<String>,
+}
+
+/// Marks the UI text node of the tooltip.
+#[derive(Component)]
+struct GizmoTooltipNode;
+
+/// Bevy system that highlights the gizmo under the cursor and shows its
+/// tooltip, or hides the tooltip if there is none.
+fn inspect_gizmo_logs(
+    mut commands: Commands,
+    mut routes: ResMut<GizmoLogRoutes>,
+    inspector: Res<GizmoInspector>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut gizmos: Gizmos<LogGizmoConfigGroup>,
+    mut tooltips: Query<(&mut Text, &mut Node, &mut Visibility), With<GizmoTooltipNode>>,
+) {
+    let drawn = routes.take_inspected(inspector.enabled);
+    let cursor = windows.get_single().ok().and_then(Window::cursor_position);
+    let picked = cursor
+        .zip(active_camera(&cameras))
+        .and_then(|(cursor, camera)| {
+            let (camera, transform) = camera;
+            let project = |point| camera.world_to_viewport(transform, point).ok();
+            Some((
+                pick(&drawn, cursor, inspector.pick_distance, project)?,
+                cursor,
+            ))
+        });
+
+    let Some((gizmo, cursor)) = picked else {
+        for (_, _, mut visibility) in &mut tooltips {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+        return;
+    };
+    let mut highlight = gizmo.command.clone();
+    highlight.map_colors(|_| Color::WHITE);
+    highlight.draw(&mut gizmos);
+
+    let text = tooltip_text(gizmo);
+    let (left, top) = (
+        Val::Px(cursor.x + TOOLTIP_OFFSET),
+        Val::Px(cursor.y + TOOLTIP_OFFSET),
+    );
+    match tooltips.get_single_mut() {
+        Ok((mut tooltip, mut node, mut visibility)) => {
+            if tooltip.0 != text {
+                tooltip.0 = text;
+            }
+            if node.left != left || node.top != top {
+                node.left = left;
+                node.top = top;
+            }
+            visibility.set_if_neq(Visibility::Inherited);
+        }
+        Err(_) => {
+            commands.spawn(tooltip_node(text, left, top));
+        }
+    }
+}
+
+/// The gizmo closest to `cursor`, if it is within `max_distance`.
+///
+/// `project` maps world positions to the viewport, like
+/// [`Camera::world_to_viewport`].
+fn pick(
+    drawn: &[InspectedGizmo],
+    cursor: Vec2,
+    max_distance: f32,
+    project: impl Fn(Vec3) -> Option<Vec2>,
+) -> Option<&InspectedGizmo> {
+    let distance = |gizmo: &InspectedGizmo| {
+        let mut lines = Lines::default();
+        lines.add(&gizmo.command);
+        if lines.segments.is_empty() {
+            // Labels have no lines, only a position.
+            let anchor = project(gizmo.command.anchor()?)?;
+            return Some(anchor.distance(cursor));
+        }
+        lines
+            .segments
+            .iter()
+            .filter_map(|segment| {
+                let (start, end) = (project(segment.start)?, project(segment.end)?);
+                Some(distance_to_segment(cursor, start, end))
+            })
+            .reduce(f32::min)
+    };
+    drawn
+        .iter()
+        .filter_map(|gizmo| Some((gizmo, distance(gizmo)?)))
+        .filter(|&(_, distance)| distance <= max_distance)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(gizmo, _)| gizmo)
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+    if length_squared == 0. {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(direction) / length_squared).clamp(0., 1.);
+    point.distance(start + t * direction)
+}
+
+/// The level, target, location, spans and message of the log event of a gizmo,
+/// one per line.
+fn tooltip_text(gizmo: &InspectedGizmo) -> String {
+    let mut lines = Vec::new();
+    if let Some(source) = &gizmo.source {
+        let metadata = source.metadata;
+        lines.push(format!("{} {}", metadata.level(), metadata.target()));
+        if let (Some(file), Some(line)) = (metadata.file(), metadata.line()) {
+            lines.push(format!("{file}:{line}"));
+        }
+        if !source.spans.is_empty() {
+            lines.push(format!("in {}", source.spans.join(" > ")));
+        }
+    }
+    if let Some(message) = &gizmo.message {
+        lines.push(message.clone());
+    }
+    lines.join("\n")
+}
+
+fn tooltip_node(text: String, left: Val, top: Val) -> impl Bundle {
+    (
+        GizmoTooltipNode,
+        Text(text),
+        TextFont {
+            font_size: 14.0,
+            ..Default::default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            left,
+            top,
+            padding: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.8)),
+        GlobalZIndex(i32::MAX),
+        Visibility::Inherited,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::Update;
+    use bevy_color::palettes::css::RED;
+    use bevy_ecs::system::RunSystemOnce;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{gizmo::line, GizmoLayer};
+
+    #[test]
+    fn test_inspect() {
+        let mut app = App::new();
+        let layer = GizmoLayer::new(&mut app, Update);
+        let inspected = |app: &mut App| {
+            app.world_mut()
+                .resource_mut::<GizmoLogRoutes>()
+                .take_inspected(true)
+        };
+        inspected(&mut app);
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            let _span = tracing::info_span!("step").entered();
+            tracing::warn!(gizmo = line(Vec3::ZERO, Vec3::X, RED), "too fast");
+        });
+        app.world_mut()
+            .run_system_once(render_gizmo_log_events)
+            .unwrap();
+        let drawn = inspected(&mut app);
+
+        let project = |point: Vec3| Some(point.truncate() * 100.);
+        assert!(pick(&drawn, Vec2::new(50., 20.), 8., project).is_none());
+        let picked = pick(&drawn, Vec2::new(50., 5.), 8., project).unwrap();
+        let text = tooltip_text(picked);
+        let [level_and_target, location, spans, message] = text.lines().collect::<Vec<_>>()[..]
+        else {
+            panic!("unexpected tooltip {text:?}");
+        };
+        assert_eq!(level_and_target, "WARN bevy_gizmo_log::inspector::tests");
+        assert!(location.starts_with("src/inspector.rs:"));
+        assert_eq!((spans, message), ("in step", "too fast"));
+    }
+}
@@ -0,0 +1,298 @@
+use bevy_app::App;
+use bevy_color::Color;
+use bevy_ecs::{
+    bundle::Bundle,
+    change_detection::DetectChangesMut,
+    component::Component,
+    query::With,
+    schedule::IntoSystemConfigs,
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy_math::{Mat4, Vec2, Vec3};
+use bevy_render::{camera::Camera, view::Visibility};
+use bevy_text::{TextColor, TextFont};
+use bevy_transform::components::GlobalTransform;
+use bevy_ui::{widget::Text, GlobalZIndex, Node, PositionType, Val};
+
+use crate::{
+    gizmo::GizmoCommand, groups::GizmoLogRoutes, render_gizmo_log_events, space::active_camera,
+};
+
+/// Bevy resource that configures how labels are drawn.
+///
+/// Labels are logged with [`gizmo::label`], or attached to gizmos logged
+/// with a message if [`label_messages`](Self::label_messages) is set:
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::{gizmo::arrow, GizmoLabelSettings, GizmoLogPlugin};
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// GizmoLogPlugin {
+///     labels: GizmoLabelSettings {
+///         label_messages: true,
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+///
+/// # let (position, velocity) = (Vec3::ZERO, Vec3::X);
+/// debug!(gizmo = arrow(position, position + velocity, RED), "{:.1}", velocity.length());
+/// ```
+///
+/// Labels are drawn as UI text nodes at the position of the gizmo as seen by
+/// the active camera with the highest order. They are drawn regardless of
+/// the config group of the gizmo.
+///
+/// [`gizmo::label`]: crate::gizmo::label
+#[derive(Resource, Clone, Debug)]
+pub struct GizmoLabelSettings {
+    /// If true, gizmos logged with a message are labeled with the message,
+    /// at their start, center or origin.
+    pub label_messages: bool,
+    /// The font size of labels.
+    pub font_size: f32,
+}
+
+impl Default for GizmoLabelSettings {
+    fn default() -> Self {
+        Self {
+            label_messages: false,
+            font_size: 14.0,
+        }
+    }
+}
+
+/// A label to draw this frame.
+pub(crate) struct QueuedLabel {
+    position: Vec3,
+    text: String,
+    color: Color,
+    /// Whether the label is the message of a log event rather than a label gizmo.
+    is_message: bool,
+}
+
+/// Queue the labels in `command`, and `message` as a label next to it.
+pub(crate) fn queue_labels(
+    command: &GizmoCommand,
+    message: Option<&str>,
+    labels: &mut Vec<QueuedLabel>,
+) {
+    command.collect_labels(Mat4::IDENTITY, labels);
+    if let (Some(message), Some(position)) = (message, command.anchor()) {
+        labels.push(QueuedLabel {
+            position,
+            text: message.to_owned(),
+            color: command.first_color().unwrap_or(Color::WHITE),
+            is_message: true,
+        });
+    }
+}
+
+impl GizmoCommand {
+    /// Where the message of a gizmo is drawn.
+    pub(crate) fn anchor(&self) -> Option<Vec3> {
+        match self {
+            Self::Arc2d { isometry, .. }
+            | Self::Ellipse2d { isometry, .. }
+            | Self::Grid2d { isometry, .. }
+            | Self::Primitive2d { isometry, .. }
+            | Self::Rect2d { isometry, .. }
+            | Self::RoundedRect2d { isometry, .. } => Some(isometry.translation.extend(0.)),
+            Self::Arc3d { isometry, .. }
+            | Self::Circle { isometry, .. }
+            | Self::Ellipse { isometry, .. }
+            | Self::Grid { isometry, .. }
+            | Self::Grid3d { isometry, .. }
+            | Self::Primitive3d { isometry, .. }
+            | Self::Rect { isometry, .. }
+            | Self::RoundedCuboid { isometry, .. }
+            | Self::RoundedRect { isometry, .. }
+            | Self::Sphere { isometry, .. } => Some(isometry.translation.into()),
+            Self::Arrow { start, .. }
+            | Self::Line { start, .. }
+            | Self::LineGradient { start, .. }
+            | Self::Ray { start, .. }
+            | Self::RayGradient { start, .. } => Some(*start),
+            Self::Arrow2d { start, .. }
+            | Self::Line2d { start, .. }
+            | Self::LineGradient2d { start, .. }
+            | Self::Ray2d { start, .. }
+            | Self::RayGradient2d { start, .. } => Some(start.extend(0.)),
+            Self::Circle2d { position, .. } => Some(position.extend(0.)),
+            Self::Axes { transform, .. }
+            | Self::Axes2d { transform, .. }
+            | Self::Cuboid { transform, .. } => Some(transform.w_axis.truncate()),
+            Self::CubicBezier { control_points, .. } => control_points.first().map(|c| c[0]),
+            Self::CubicBezier2d { control_points, .. } => {
+                control_points.first().map(|c| c[0].extend(0.))
+            }
+            Self::Linestrip { positions, .. } => positions.first().copied(),
+            Self::Linestrip2d { positions, .. } => positions.first().map(|p| p.extend(0.)),
+            Self::LinestripGradient { points } => points.first().map(|(p, _)| *p),
+            Self::LinestripGradient2d { positions } => positions.first().map(|(p, _)| p.extend(0.)),
+            Self::LongArc3dBetween { center, .. } | Self::ShortArc3dBetween { center, .. } => {
+                Some(*center)
+            }
+            Self::Label { position, .. } => Some(*position),
+            Self::Batch { gizmos } => gizmos.iter().find_map(GizmoCommand::anchor),
+            Self::Transformed { transform, gizmo } => gizmo
+                .anchor()
+                .map(|anchor| transform.transform_point3(anchor)),
+            Self::Clear { .. } => None,
+        }
+    }
+
+    /// Push the label gizmos in the command, transformed by `transform`.
+    fn collect_labels(&self, transform: Mat4, labels: &mut Vec<QueuedLabel>) {
+        match self {
+            Self::Label {
+                position,
+                text,
+                color,
+            } => labels.push(QueuedLabel {
+                position: transform.transform_point3(*position),
+                text: text.clone(),
+                color: *color,
+                is_message: false,
+            }),
+            Self::Transformed {
+                transform: local,
+                gizmo,
+            } => gizmo.collect_labels(transform * *local, labels),
+            Self::Batch { gizmos } => {
+                for gizmo in gizmos {
+                    gizmo.collect_labels(transform, labels);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn first_color(&self) -> Option<Color> {
+        let mut first = None;
+        self.clone().map_colors(|color| *first.get_or_insert(color));
+        first
+    }
+}
+
+/// Marks the UI text nodes that draw labels. They are reused every frame.
+#[derive(Component)]
+struct GizmoLabelNode;
+
+/// Register [`draw_gizmo_labels`]. Requires [`GizmoLogRoutes`].
+pub(crate) fn init_labels(app: &mut App) {
+    app.init_resource::<GizmoLabelSettings>();
+    let schedule = app.world().resource::<GizmoLogRoutes>().schedule();
+    app.add_systems(schedule, draw_gizmo_labels.after(render_gizmo_log_events));
+}
+
+/// Bevy system that draws the labels queued this frame with a pool of
+/// [`GizmoLabelNode`]s, spawning more nodes when needed and hiding the
+/// nodes that aren't.
+fn draw_gizmo_labels(
+    mut commands: Commands,
+    mut routes: ResMut<GizmoLogRoutes>,
+    settings: Res<GizmoLabelSettings>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut nodes: Query<
+        (
+            &mut Text,
+            &mut TextFont,
+            &mut TextColor,
+            &mut Node,
+            &mut Visibility,
+        ),
+        With<GizmoLabelNode>,
+    >,
+) {
+    let camera = active_camera(&cameras);
+    let mut labels = routes
+        .take_labels()
+        .into_iter()
+        .filter(|label| settings.label_messages || !label.is_message)
+        .filter_map(|label| {
+            let (camera, transform) = camera?;
+            let position = camera.world_to_viewport(transform, label.position).ok()?;
+            Some((position, label))
+        });
+
+    for (mut text, mut font, mut color, mut node, mut visibility) in &mut nodes {
+        let Some((position, label)) = labels.next() else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        // Only touch what changed, so that text isn't laid out again every frame.
+        if text.0 != label.text {
+            text.0 = label.text;
+        }
+        if font.font_size != settings.font_size {
+            font.font_size = settings.font_size;
+        }
+        if color.0 != label.color {
+            color.0 = label.color;
+        }
+        let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+        if node.left != left || node.top != top {
+            node.left = left;
+            node.top = top;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+    for (position, label) in labels {
+        commands.spawn(label_node(position, label, settings.font_size));
+    }
+}
+
+fn label_node(position: Vec2, label: QueuedLabel, font_size: f32) -> impl Bundle {
+    (
+        GizmoLabelNode,
+        Text(label.text),
+        TextFont {
+            font_size,
+            ..Default::default()
+        },
+        TextColor(label.color),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x),
+            top: Val::Px(position.y),
+            ..Default::default()
+        },
+        GlobalZIndex(i32::MAX),
+        Visibility::Inherited,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+
+    use super::*;
+
+    #[test]
+    fn test_queue_labels() {
+        let command = GizmoCommand::Transformed {
+            transform: Mat4::from_translation(Vec3::X),
+            gizmo: Box::new(GizmoCommand::Label {
+                position: Vec3::Y,
+                text: "cost".to_owned(),
+                color: RED.into(),
+            }),
+        };
+        let mut labels = Vec::new();
+        queue_labels(&command, Some("message"), &mut labels);
+
+        let [label, message] = &labels[..] else {
+            panic!("expected a label and a message");
+        };
+        assert_eq!(
+            (label.position, label.text.as_str()),
+            (Vec3::new(1., 1., 0.), "cost")
+        );
+        assert!(!label.is_message);
+        assert_eq!(message.position, Vec3::new(1., 1., 0.));
+        assert_eq!(message.color, Color::from(RED));
+        assert!(message.is_message);
+    }
+}
@@ -0,0 +1,264 @@
+use bevy_app::App;
+use bevy_color::{Alpha, Color, Mix};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use bevy_gizmos::config::{GizmoConfig, GizmoConfigGroup, GizmoConfigStore, GizmoLineStyle};
+use bevy_reflect::Reflect;
+use tracing::Level;
+
+use crate::{
+    gizmo::GizmoCommand,
+    groups::{self, GizmoLogRoutes, LogGizmoConfigGroup},
+    render_gizmo_log_events,
+};
+
+/// How gizmos logged at one level are drawn.
+///
+/// The default style draws gizmos unchanged.
+#[derive(Clone, Debug)]
+pub struct GizmoLevelStyle {
+    /// If true, gizmos logged at this level are ignored, as if they were filtered out.
+    pub hidden: bool,
+    /// Mixes the colors of gizmos with a color, by a factor between 0 and 1.
+    pub tint: Option<(Color, f32)>,
+    /// Multiplies the alpha of the colors of gizmos, to dim them.
+    pub opacity: f32,
+    /// Overrides [`GizmoConfig::line_width`] to draw gizmos thinner or thicker.
+    pub line_width: Option<f32>,
+    /// Overrides [`GizmoConfig::depth_bias`]. `-1.0` draws gizmos on top of everything.
+    pub depth_bias: Option<f32>,
+    /// Overrides [`GizmoConfig::line_style`], e.g. to draw gizmos dotted.
+    pub line_style: Option<GizmoLineStyle>,
+}
+
+impl Default for GizmoLevelStyle {
+    fn default() -> Self {
+        Self {
+            hidden: false,
+            tint: None,
+            opacity: 1.0,
+            line_width: None,
+            depth_bias: None,
+            line_style: None,
+        }
+    }
+}
+
+impl GizmoLevelStyle {
+    /// Whether gizmos need to be drawn in a config group of their own.
+    pub(crate) fn overrides_config(&self) -> bool {
+        self.line_width.is_some() || self.depth_bias.is_some() || self.line_style.is_some()
+    }
+
+    fn apply_config(&self, config: &mut GizmoConfig) {
+        if let Some(line_width) = self.line_width {
+            config.line_width = line_width;
+        }
+        if let Some(depth_bias) = self.depth_bias {
+            config.depth_bias = depth_bias;
+        }
+        if let Some(line_style) = self.line_style {
+            config.line_style = line_style;
+        }
+    }
+
+    /// Whether [`apply_colors`](Self::apply_colors) changes any colors.
+    pub(crate) fn changes_colors(&self) -> bool {
+        self.tint.is_some() || self.opacity != 1.0
+    }
+
+    pub(crate) fn apply_colors(&self, command: &mut GizmoCommand) {
+        if !self.changes_colors() {
+            return;
+        }
+        command.map_colors(|mut color| {
+            if let Some((tint, factor)) = self.tint {
+                color = color.mix(&tint, factor);
+            }
+            color.with_alpha(color.alpha() * self.opacity)
+        });
+    }
+}
+
+/// Bevy resource that styles gizmos by the level they were logged at.
+///
+/// Set it with [`GizmoLogPlugin::level_styles`], or change the resource
+/// while the app is running.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::{GizmoLevelStyle, GizmoLevelStyles, GizmoLogPlugin};
+/// # use bevy_gizmos::config::GizmoLineStyle;
+/// GizmoLogPlugin {
+///     level_styles: GizmoLevelStyles {
+///         trace: GizmoLevelStyle {
+///             opacity: 0.5,
+///             line_style: Some(GizmoLineStyle::Dotted),
+///             ..Default::default()
+///         },
+///         warn: GizmoLevelStyle {
+///             tint: Some((RED.into(), 0.5)),
+///             line_width: Some(4.0),
+///             depth_bias: Some(-1.0),
+///             ..Default::default()
+///         },
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// ```
+///
+/// Line width, depth bias and line style are applied by drawing gizmos in a
+/// config group per level, whose config is copied from [`LogGizmoConfigGroup`]
+/// every frame. Gizmos routed to other config groups with
+/// [`AppGizmoLogBuilder`] keep the config of their group.
+///
+/// Styles are applied whenever gizmos are drawn, so changing this resource
+/// also restyles gizmos that are retained for more than one frame.
+///
+/// [`GizmoLogPlugin::level_styles`]: crate::GizmoLogPlugin::level_styles
+/// [`AppGizmoLogBuilder`]: crate::AppGizmoLogBuilder
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GizmoLevelStyles {
+    pub trace: GizmoLevelStyle,
+    pub debug: GizmoLevelStyle,
+    pub info: GizmoLevelStyle,
+    pub warn: GizmoLevelStyle,
+    pub error: GizmoLevelStyle,
+}
+
+impl GizmoLevelStyles {
+    /// The style of gizmos logged at `level`.
+    pub fn get(&self, level: Level) -> &GizmoLevelStyle {
+        match level {
+            Level::TRACE => &self.trace,
+            Level::DEBUG => &self.debug,
+            Level::INFO => &self.info,
+            Level::WARN => &self.warn,
+            Level::ERROR => &self.error,
+        }
+    }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct TraceGizmoConfigGroup;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct DebugGizmoConfigGroup;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct InfoGizmoConfigGroup;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct WarnGizmoConfigGroup;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct ErrorGizmoConfigGroup;
+
+/// Register a config group per level. Requires [`GizmoLogRoutes`].
+pub(crate) fn init_level_groups(app: &mut App) {
+    app.init_resource::<GizmoLevelStyles>();
+    groups::route_level::<TraceGizmoConfigGroup>(app, Level::TRACE);
+    groups::route_level::<DebugGizmoConfigGroup>(app, Level::DEBUG);
+    groups::route_level::<InfoGizmoConfigGroup>(app, Level::INFO);
+    groups::route_level::<WarnGizmoConfigGroup>(app, Level::WARN);
+    groups::route_level::<ErrorGizmoConfigGroup>(app, Level::ERROR);
+    let schedule = app.world().resource::<GizmoLogRoutes>().schedule();
+    app.add_systems(
+        schedule,
+        sync_level_gizmo_configs.before(render_gizmo_log_events),
+    );
+}
+
+/// Bevy system that copies the config of [`LogGizmoConfigGroup`] to the
+/// config group of each level and applies the level's style.
+fn sync_level_gizmo_configs(
+    mut config_store: ResMut<GizmoConfigStore>,
+    styles: Res<GizmoLevelStyles>,
+) {
+    let (config, _) = config_store.config::<LogGizmoConfigGroup>();
+    let config = config.clone();
+    let sync = |level_config: &mut GizmoConfig, style: &GizmoLevelStyle| {
+        *level_config = config.clone();
+        style.apply_config(level_config);
+    };
+    sync(
+        config_store.config_mut::<TraceGizmoConfigGroup>().0,
+        &styles.trace,
+    );
+    sync(
+        config_store.config_mut::<DebugGizmoConfigGroup>().0,
+        &styles.debug,
+    );
+    sync(
+        config_store.config_mut::<InfoGizmoConfigGroup>().0,
+        &styles.info,
+    );
+    sync(
+        config_store.config_mut::<WarnGizmoConfigGroup>().0,
+        &styles.warn,
+    );
+    sync(
+        config_store.config_mut::<ErrorGizmoConfigGroup>().0,
+        &styles.error,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::Update;
+    use bevy_color::palettes::css::{BLUE, RED};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec3;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{diagnostics::GizmoLogStats, gizmo::line, GizmoLayer};
+
+    #[test]
+    fn test_apply_colors() {
+        let style = GizmoLevelStyle {
+            tint: Some((BLUE.into(), 1.0)),
+            opacity: 0.5,
+            ..Default::default()
+        };
+        let mut command = GizmoCommand::Line {
+            start: Vec3::ZERO,
+            end: Vec3::ONE,
+            color: RED.into(),
+        };
+        style.apply_colors(&mut command);
+        let GizmoCommand::Line { color, .. } = command else {
+            unreachable!();
+        };
+        assert_eq!(color.to_srgba(), BLUE.with_alpha(0.5));
+    }
+
+    #[test]
+    fn test_restyle_retained() {
+        let mut app = App::new();
+        app.init_resource::<GizmoLogStats>();
+        let layer = GizmoLayer::new(&mut app, Update);
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(
+                gizmo = line(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.key = "target"
+            );
+        });
+        let render = |app: &mut App, hidden: bool| {
+            app.world_mut()
+                .resource_mut::<GizmoLevelStyles>()
+                .debug
+                .hidden = hidden;
+            app.world_mut()
+                .run_system_once(render_gizmo_log_events)
+                .unwrap();
+            std::mem::take(&mut app.world_mut().resource_mut::<GizmoLogStats>().rendered)
+        };
+        // Hiding a level hides the gizmos it retained, until it is shown again.
+        assert_eq!(render(&mut app, true), 0);
+        assert_eq!(render(&mut app, false), 1);
+    }
+}
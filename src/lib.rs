@@ -51,16 +51,351 @@
 //! debug!(gizmo = axes(Transform::default(), 1.0));
 //! ```
 //!
+//! The strings follow a versioned format, documented in [`gizmo::wire`],
+//! so that gizmos can be logged and rendered by different versions of this
+//! crate, or by other tools.
+//!
+//! If a gizmo doesn't show up, set [`GizmoLogPlugin::diagnostics`] or add
+//! [`GizmoLogDiagnosticsPlugin`]. Gizmo fields that can't be drawn are then
+//! reported with a warning once per callsite, and the number of gizmos
+//! parsed, dropped and rendered is recorded as bevy diagnostics.
+//!
+//! Gizmo logs wait in a queue until they are drawn. If they are logged faster
+//! than they are drawn, for example while the schedule of
+//! [`render_gizmo_log_events`] doesn't run, the oldest ones are dropped past
+//! [`GizmoLayer::DEFAULT_CAPACITY`]. [`GizmoLogPlugin::queue_capacity`] and
+//! [`GizmoLogPlugin::queue_overflow`] change the capacity and what is
+//! dropped, see [`GizmoOverflow`].
+//!
+//! # Typed gizmos
+//!
+//! The functions in [`gizmo`] return strings, which need to be parsed before
+//! they can be rendered. When logging many gizmos per frame, use the functions
+//! in [`gizmo::typed`] instead. They return a [`GizmoValue`] that is logged
+//! with the `%` sigil and skips the conversion to and from text:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! use bevy_gizmo_log::gizmo::typed::arrow;
+//!
+//! debug!(gizmo = %arrow(Vec3::ZERO, Vec3::ONE, RED));
+//! ```
+//!
+//! A [`GizmoValue`] also has the options of the builders returned by bevy's
+//! `Gizmos` methods, such as the resolution of circles and spheres or the tip
+//! length of arrows:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! use bevy_gizmo_log::gizmo::typed::sphere;
+//!
+//! debug!(gizmo = %sphere(Vec3::ZERO, 1.0, RED).resolution(64));
+//! ```
+//!
+//! Each log event has a cost of its own. To log many gizmos as one event,
+//! collect them in a [`GizmoBatch`](gizmo::GizmoBatch):
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec2;
+//! use bevy_gizmo_log::gizmo::{typed::circle_2d, GizmoBatch};
+//!
+//! # let particles = vec![Vec2::ZERO; 250];
+//! let batch: GizmoBatch = particles.iter().map(|&p| circle_2d(p, 1.0, RED)).collect();
+//! debug!(gizmo = %batch);
+//! ```
+//!
+//! # Persistent gizmos
+//!
+//! Logged gizmos are drawn for a single frame. To keep drawing a gizmo
+//! for longer, log it with a `gizmo.ttl_secs` or `gizmo.ttl_frames` field:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_gizmo_log::gizmo::sphere;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! debug!(gizmo = sphere(Vec3::ZERO, 1.0, RED), gizmo.ttl_secs = 2.0);
+//! ```
+//!
+//! Gizmos logged with a `gizmo.key` field are drawn every frame until
+//! another gizmo is logged with the same key, or until the key is cleared
+//! with [`gizmo::clear`]. This is useful for code that runs less often than
+//! once per frame:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_gizmo_log::gizmo::{clear, sphere};
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! debug!(gizmo = sphere(Vec3::ZERO, 1.0, RED), gizmo.key = "target");
+//! debug!(gizmo = clear("target"));
+//! ```
+//!
+//! Persistent and keyed gizmos are stored in the [`RetainedGizmos`] resource.
+//!
+//! A gizmo logged with a `gizmo.entity` field is drawn relative to the
+//! `GlobalTransform` of that entity, so that it follows the entity. Combined
+//! with a key, a gizmo logged once keeps following the entity until it is
+//! cleared or the entity is despawned:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_ecs::entity::Entity;
+//! # use bevy_gizmo_log::gizmo::line;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! # let npc = Entity::PLACEHOLDER;
+//! # let target = Vec3::X;
+//! debug!(
+//!     gizmo = line(Vec3::ZERO, target, RED),
+//!     gizmo.entity = npc.to_bits(),
+//!     gizmo.key = "npc target",
+//! );
+//! ```
+//!
+//! Gizmos logged in `FixedUpdate` are drawn in the frame they were logged
+//! in, so they pile up in frames with several fixed timestep ticks and are
+//! missing from frames with none. With [`GizmoLogPlugin::fixed_ticks`], the
+//! gizmos of the last tick are drawn instead, every frame until the next
+//! tick completes.
+//!
+//! # Labels
+//!
+//! With the `labels` feature, [`gizmo::label`] logs text that is drawn at a
+//! position, facing the screen. With [`GizmoLabelSettings::label_messages`],
+//! the message of a gizmo log is drawn next to the gizmo too:
+//!
+//! ```
+//! # use bevy_color::palettes::css::{RED, WHITE};
+//! # use bevy_gizmo_log::gizmo::{arrow, label};
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! # let (node, cost) = (Vec3::ZERO, 1.5);
+//! # let (position, velocity) = (Vec3::ZERO, Vec3::X);
+//! debug!(gizmo = label(node, format!("{cost:.1}"), WHITE));
+//! debug!(gizmo = arrow(position, position + velocity, RED), "{:.1}", velocity.length());
+//! ```
+//!
+//! # Inspecting gizmos
+//!
+//! With the `labels` feature, add [`GizmoInspectorPlugin`] to find out which
+//! log drew a gizmo. Hovering the cursor over a logged gizmo highlights it and
+//! shows a tooltip with the level, target, file, line, spans and message of
+//! the log event:
+//!
+//! ```no_run
+//! # #[cfg(feature = "labels")] {
+//! # use bevy::DefaultPlugins;
+//! # use bevy_app::{App, PluginGroup};
+//! # use bevy_gizmo_log::{GizmoInspectorPlugin, GizmoLogPlugin};
+//! # use bevy_log::LogPlugin;
+//! App::new()
+//!     .add_plugins(DefaultPlugins.build().disable::<LogPlugin>())
+//!     .add_plugins((GizmoLogPlugin::default(), GizmoInspectorPlugin));
+//! # }
+//! ```
+//!
+//! # Coordinate frames
+//!
+//! Code that works in local coordinates can log gizmos inside a span with a
+//! `gizmo.transform` field, instead of transforming every point to world
+//! space. Nested spans compose their transforms:
+//!
+//! ```
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_gizmo_log::gizmo::{sphere, transform};
+//! # use bevy_log::{debug, info_span};
+//! # use bevy_math::Vec3;
+//! # use bevy_transform::components::Transform;
+//! # let agent_transform = Transform::default();
+//! let _span = info_span!("agent", gizmo.transform = transform(agent_transform)).entered();
+//! debug!(gizmo = sphere(Vec3::Y, 1.0, RED));
+//! ```
+//!
+//! # Screen space
+//!
+//! Gizmos are logged in world coordinates by default. For HUD-like output,
+//! log them with a `gizmo.space` field of `"viewport"`, where (0, 0) is the
+//! top left corner of the viewport and (1, 1) the bottom right one, or
+//! `"pixels"`, in logical pixels from the top left corner:
+//!
+//! ```
+//! # use bevy_color::palettes::css::{GREEN, WHITE};
+//! # use bevy_gizmo_log::gizmo::{arrow_2d, rect_2d};
+//! # use bevy_log::debug;
+//! # use bevy_math::{Isometry2d, Vec2};
+//! # let (input, frame_time) = (Vec2::X, 0.01);
+//! let center = Vec2::new(100., 100.);
+//! debug!(gizmo = arrow_2d(center, center + input * 50., WHITE), gizmo.space = "pixels");
+//! let bar = Vec2::new(frame_time * 30., 0.02);
+//! debug!(
+//!     gizmo = rect_2d(Isometry2d::from_translation(Vec2::new(0.05, 0.05) + bar / 2.), bar, GREEN),
+//!     gizmo.space = "viewport",
+//! );
+//! ```
+//!
+//! They are converted to world space through the active camera with the
+//! highest order, on a plane close to its near plane, so that they are
+//! drawn in front of the scene.
+//!
+//! # Config groups
+//!
+//! Logged gizmos are drawn in the [`LogGizmoConfigGroup`] config group, so
+//! that their line width, depth bias and render layers can be configured, and
+//! they can be toggled separately from other gizmos. [`AppGizmoLogBuilder`]
+//! routes gizmos to your own config groups by their `gizmo.group` field or
+//! their target:
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_gizmo_log::{gizmo::sphere, AppGizmoLogBuilder};
+//! # use bevy_gizmos::config::GizmoConfigGroup;
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! # use bevy_reflect::Reflect;
+//! # let mut app = App::new();
+//! #[derive(Default, Reflect, GizmoConfigGroup)]
+//! struct PhysicsGizmos;
+//!
+//! app.route_gizmo_log_group::<PhysicsGizmos>("physics");
+//! debug!(gizmo = sphere(Vec3::ZERO, 1.0, RED), gizmo.group = "physics");
+//! ```
+//!
+//! [`GizmoLogPlugin::level_styles`] styles gizmos by the level they were
+//! logged at, so that e.g. gizmos logged with `warn!` are drawn thicker and on
+//! top of everything, and gizmos logged with `trace!` are dimmed and dotted.
+//!
+//! # Filtering
+//!
+//! Which gizmo logs are drawn is decided by [`GizmoLogPlugin::gizmo_filter`],
+//! separately from the `filter` and `level` of logs written to stderr. It
+//! uses the same directives as `RUST_LOG` and can be changed while the app
+//! is running through the [`GizmoFilter`] resource:
+//!
+//! ```
+//! # use bevy_ecs::system::ResMut;
+//! # use bevy_gizmo_log::GizmoFilter;
+//! fn show_physics_gizmos(mut filter: ResMut<GizmoFilter>) {
+//!     filter.set("warn,my_game::physics=trace").unwrap();
+//! }
+//! ```
+//!
+//! The directives only filter events: spans pass at every level, so that a
+//! `gizmo.transform` span applies to the gizmos logged in it whatever its
+//! level.
+//!
+//! # Recording
+//!
+//! Gizmo logs can be written to a file with [`GizmoRecorder`] or by setting
+//! [`GizmoLogPlugin::record_to`]. Each line of the file is a [`GizmoRecord`]
+//! with the frame number, timestamp, level, target and spans of the event.
+//!
+//! ```no_run
+//! # use bevy_gizmo_log::GizmoLogPlugin;
+//! GizmoLogPlugin {
+//!     record_to: Some("gizmos.ron".into()),
+//!     ..Default::default()
+//! };
+//! ```
+//!
+//! Recordings can be played back with [`GizmoReplayPlugin`], which can be
+//! paused, stepped, seeked and sped up through the [`GizmoReplay`] resource.
+//!
+//! # Testing
+//!
+//! [`with_captured_gizmos`] runs a closure and returns every gizmo it logged
+//! as a [`GizmoCommand`], without bevy, so that tests can assert on them:
+//!
+//! ```
+//! # use bevy_color::{palettes::css::RED, Color};
+//! # use bevy_log::debug;
+//! # use bevy_math::Vec3;
+//! use bevy_gizmo_log::{
+//!     gizmo::{line, GizmoCommand},
+//!     with_captured_gizmos,
+//! };
+//!
+//! let gizmos = with_captured_gizmos(|| {
+//!     debug!(gizmo = line(Vec3::ZERO, Vec3::X, RED));
+//! });
+//! assert_eq!(
+//!     gizmos,
+//!     [GizmoCommand::Line {
+//!         start: Vec3::ZERO,
+//!         end: Vec3::X,
+//!         color: Color::from(RED),
+//!     }]
+//! );
+//! ```
+//!
+//! [`GizmoCapture`] is the underlying layer, for when a custom subscriber is needed.
+//!
+//! # SVG export
+//!
+//! 2D gizmos can be rendered to an SVG file without bevy, e.g. from a test or
+//! a command line tool, with [`GizmoSvg`]. It accepts gizmo strings, typed
+//! gizmos and recordings:
+//!
+//! ```no_run
+//! # use std::{fs::File, io::BufReader};
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_math::Vec2;
+//! use bevy_gizmo_log::{gizmo::circle_2d, GizmoSvg};
+//!
+//! let mut svg = GizmoSvg::new();
+//! svg.add_str(&circle_2d(Vec2::ZERO, 10.0, RED)).unwrap();
+//! svg.add_recording(BufReader::new(File::open("gizmos.ron").unwrap()))
+//!     .unwrap();
+//! svg.save("gizmos.svg").unwrap();
+//! ```
+//!
+//! # Snapshot tests
+//!
+//! With the `png` feature, [`GizmoImage`] draws gizmos into an image on the
+//! CPU, so that debug visualizations can be snapshot tested on CI where bevy
+//! can't render. 3D gizmos are projected with a view-projection matrix.
+//!
+//! ```no_run
+//! # #[cfg(feature = "png")] {
+//! # use bevy_color::palettes::css::RED;
+//! # use bevy_math::Vec2;
+//! use bevy_gizmo_log::{gizmo::circle_2d, GizmoImage};
+//!
+//! let mut image = GizmoImage::new(256, 256);
+//! image.draw_str(&circle_2d(Vec2::ZERO, 100.0, RED)).unwrap();
+//! image.compare_golden("tests/golden/circle.png", 0).unwrap();
+//! # }
+//! ```
+//!
 //! # Feature flags
 //!
 //! **bevy:** Enabled by default. The bevy feature enables reading
 //! gizmo logs in bevy and rendering them. You can disable this feature if
 //! your crate needs to generate gizmo logs but doesn't need to consume them.
 //!
+//! **labels:** Draws label gizmos and log messages as
+//! text with bevy_ui. See [`GizmoLabelSettings`]. Also adds
+//! [`GizmoInspectorPlugin`].
+//!
 //! **convert-nalgebra033:** Adds a module `gizmo_na` which lets you
 //! create gizmos using nalgebra types instead of bevy's default glam types.
 //!
+//! **png:** Adds [`GizmoImage`], a CPU rasterizer that draws gizmo logs
+//! into PNG images and compares them with golden images.
+//!
+//! **cbor:** Adds a compact binary encoding of gizmo logs, selected with
+//! [`gizmo::wire::set_encoding`]. Gizmos in either encoding are decoded.
+//!
 //! [`LogPlugin`]: bevy_log::LogPlugin
+//! [`GizmoValue`]: gizmo::GizmoValue
+//! [`GizmoCommand`]: gizmo::GizmoCommand
 
 // only enables the `doc_cfg` feature when
 // the `docsrs` configuration attribute is defined
@@ -72,6 +407,24 @@ pub mod gizmo;
 #[cfg_attr(docsrs, doc(cfg(feature = "convert-nalgebra033")))]
 pub mod gizmo_na;
 
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod diagnostics;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod filter;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod groups;
+#[cfg(feature = "labels")]
+#[cfg_attr(docsrs, doc(cfg(feature = "labels")))]
+mod inspector;
+#[cfg(feature = "labels")]
+#[cfg_attr(docsrs, doc(cfg(feature = "labels")))]
+mod labels;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod level_style;
 #[cfg(feature = "bevy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
 mod log_layer;
@@ -79,6 +432,54 @@ mod log_layer;
 #[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
 mod log_plugin;
 
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod queue;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod recorder;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod replay;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod retained;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod space;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+mod ticks;
+
+#[cfg(feature = "png")]
+#[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+mod raster;
+
+mod capture;
+mod frame;
+mod record;
+mod svg;
+mod tessellate;
+mod visitor;
+
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use diagnostics::GizmoLogDiagnosticsPlugin;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use filter::{GizmoFilter, GizmoFilterError, ReloadableGizmoFilter};
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use groups::{AppGizmoLogBuilder, LogGizmoConfigGroup};
+#[cfg(feature = "labels")]
+#[cfg_attr(docsrs, doc(cfg(feature = "labels")))]
+pub use inspector::{GizmoInspector, GizmoInspectorPlugin};
+#[cfg(feature = "labels")]
+#[cfg_attr(docsrs, doc(cfg(feature = "labels")))]
+pub use labels::GizmoLabelSettings;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use level_style::{GizmoLevelStyle, GizmoLevelStyles};
 #[cfg(feature = "bevy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
 pub use log_layer::render_gizmo_log_events;
@@ -88,5 +489,25 @@ pub use log_layer::GizmoLayer;
 #[cfg(feature = "bevy")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
 pub use log_plugin::GizmoLogPlugin;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use queue::GizmoOverflow;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use recorder::GizmoRecorder;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use replay::{GizmoReplay, GizmoReplayPlugin};
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub use retained::RetainedGizmos;
+
+#[cfg(feature = "png")]
+#[cfg_attr(docsrs, doc(cfg(feature = "png")))]
+pub use raster::{GizmoImage, GoldenError, UPDATE_GOLDEN_VAR};
+
+pub use capture::{with_captured_gizmos, GizmoCapture};
+pub use record::{read_recording, GizmoRecord, RecordingError};
+pub use svg::GizmoSvg;
 
 mod transform;
@@ -1,15 +1,32 @@
-use std::sync::mpsc;
-
-use bevy_app::App;
-use bevy_ecs::{schedule::ScheduleLabel, system::NonSend};
-use bevy_gizmos::gizmos::Gizmos;
-use tracing::{
-    field::{Field, Visit},
-    Event, Subscriber,
+use std::{borrow::Cow, collections::HashSet, sync::Arc};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    entity::Entity,
+    schedule::ScheduleLabel,
+    system::{Local, Query, Res, ResMut, Resource},
 };
-use tracing_subscriber::{layer::Context, Layer};
+use bevy_gizmos::{gizmos::Gizmos, AppGizmoBuilder};
+use bevy_render::camera::Camera;
+use bevy_time::Time;
+use bevy_transform::components::GlobalTransform;
+use tracing::{span, Event, Metadata, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 
-use crate::gizmo::GizmoCommand;
+#[cfg(feature = "labels")]
+use crate::labels::init_labels;
+use crate::{
+    diagnostics::GizmoLogStats,
+    frame,
+    gizmo::{wire::DecodeError, GizmoCommand},
+    groups::{GizmoLogRoutes, LogGizmoConfigGroup},
+    level_style::{init_level_groups, GizmoLevelStyles},
+    queue::{GizmoOverflow, GizmoQueue},
+    retained::{GizmoSource, PlacedGizmo, RetainedGizmos},
+    space::active_camera,
+    ticks::{init_ticks, FixedTicks, TickGizmos},
+    visitor::{extract_gizmo_command, GizmoFieldError, LoggedGizmo},
+};
 
 /// A [`tracing_subscriber::Layer`] that handles gizmo logs.
 ///
@@ -18,55 +35,139 @@ use crate::gizmo::GizmoCommand;
 /// [`tracing_subscriber::Layer`]: tracing_subscriber::Layer
 /// [`GizmoLogPlugin`]: crate::GizmoLogPlugin
 pub struct GizmoLayer {
-    sender: mpsc::Sender<GizmoCommand>,
+    queue: Arc<GizmoQueue<ReceivedGizmo>>,
+    ticks: FixedTicks,
+    fixed_ticks: bool,
 }
 
 impl GizmoLayer {
+    /// The default number of gizmo logs that can wait for [`render_gizmo_log_events`].
+    pub const DEFAULT_CAPACITY: usize = 65_536;
+
     /// Create a new [`GizmoLayer`] and setup `app` to render gizmos from logs.
     ///
     /// `schedule` is the schedule used by [`render_gizmo_log_events`] to render gizmos.
+    ///
+    /// Up to [`DEFAULT_CAPACITY`](Self::DEFAULT_CAPACITY) gizmo logs are
+    /// queued until they are drawn, dropping the oldest ones past that.
     pub fn new(app: &mut App, schedule: impl ScheduleLabel) -> Self {
-        let (sender, receiver) = std::sync::mpsc::channel();
-        app.insert_non_send_resource(GizmoLogEventReceiver(receiver));
+        let queue = Arc::new(GizmoQueue::new(
+            Self::DEFAULT_CAPACITY,
+            GizmoOverflow::default(),
+        ));
+        app.insert_resource(GizmoLogEventReceiver(queue.clone()));
+        app.add_systems(First, advance_gizmo_queue_frame);
+        let ticks = init_ticks(app);
+        app.init_resource::<RetainedGizmos>();
+        app.insert_resource(GizmoLogRoutes::new(schedule.intern()));
+        app.init_gizmo_group::<LogGizmoConfigGroup>();
         app.add_systems(schedule, render_gizmo_log_events);
-        GizmoLayer { sender }
+        init_level_groups(app);
+        #[cfg(feature = "labels")]
+        init_labels(app);
+        GizmoLayer {
+            queue,
+            ticks,
+            fixed_ticks: false,
+        }
     }
-}
 
-impl<S: Subscriber> Layer<S> for GizmoLayer {
-    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
-        if let Some(gizmo_command) = extract_gizmo_command(event) {
-            let _ = self.sender.send(gizmo_command);
-        }
+    /// Queue at most `capacity` gizmo logs until they are drawn, and handle
+    /// the ones past that according to `overflow`.
+    ///
+    /// ```
+    /// # use bevy_app::{App, PostUpdate};
+    /// # use bevy_gizmo_log::{GizmoLayer, GizmoOverflow};
+    /// # let mut app = App::new();
+    /// let layer = GizmoLayer::new(&mut app, PostUpdate).with_capacity(1024, GizmoOverflow::LastFrameOnly);
+    /// ```
+    pub fn with_capacity(self, capacity: usize, overflow: GizmoOverflow) -> Self {
+        self.queue.configure(capacity, overflow);
+        self
+    }
+
+    /// If `enabled`, attribute the gizmos logged in the fixed timestep
+    /// schedules, such as `FixedUpdate`, to the tick they were logged in.
+    ///
+    /// [`render_gizmo_log_events`] then draws the gizmos of the last tick
+    /// that completed, every frame until the next tick completes, instead of
+    /// drawing every gizmo once in the frame it was logged in. Gizmos of
+    /// earlier ticks that completed in the same frame aren't drawn, and
+    /// gizmos are still drawn in frames without ticks.
+    ///
+    /// Only gizmos logged on the thread that runs the fixed timestep are
+    /// attributed to its ticks. Run the schedules that log gizmos with the
+    /// single-threaded executor, as [`GizmoLogPlugin::fixed_ticks`] does:
+    ///
+    /// ```
+    /// # use bevy_app::{App, FixedUpdate, PostUpdate};
+    /// # use bevy_ecs::schedule::ExecutorKind;
+    /// # use bevy_gizmo_log::GizmoLayer;
+    /// # let mut app = App::new();
+    /// let layer = GizmoLayer::new(&mut app, PostUpdate).with_fixed_ticks(true);
+    /// app.edit_schedule(FixedUpdate, |schedule| {
+    ///     schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    /// });
+    /// ```
+    ///
+    /// [`GizmoLogPlugin::fixed_ticks`]: crate::GizmoLogPlugin::fixed_ticks
+    ///
+    /// Persistent and keyed gizmos are retained as usual, whichever tick
+    /// they were logged in.
+    pub fn with_fixed_ticks(mut self, enabled: bool) -> Self {
+        self.fixed_ticks = enabled;
+        self
     }
 }
 
-/// Visitor that extracts the gizmo field of an event into a GizmoCommand.
-struct GizmoVisitor(Option<GizmoCommand>);
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for GizmoLayer {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        frame::on_new_span(attrs, id, &ctx);
+    }
 
-impl Visit for GizmoVisitor {
-    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {
-        // Do nothing. We only support extracting from strings.
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        frame::on_record(id, values, &ctx);
     }
 
-    fn record_str(&mut self, field: &Field, value: &str) {
-        if field.name() == "gizmo" {
-            if let Ok(gizmo_command) = ron::de::from_str(value) {
-                self.0 = Some(gizmo_command);
-            }
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(result) = extract_gizmo_command(event) {
+            self.queue.push(ReceivedGizmo {
+                result: result.map(|mut logged_gizmo| {
+                    logged_gizmo.command = frame::apply(logged_gizmo.command, event, &ctx);
+                    logged_gizmo
+                }),
+                metadata: event.metadata(),
+                tick: self.fixed_ticks.then(|| self.ticks.current()).flatten(),
+                spans: ctx
+                    .event_scope(event)
+                    .map(|scope| scope.from_root().map(|span| span.name()).collect())
+                    .unwrap_or_default(),
+            });
         }
     }
 }
 
-/// Convenience function for creating a one-off visitor and using it on one event.
-fn extract_gizmo_command(event: &Event<'_>) -> Option<GizmoCommand> {
-    let mut visitor = GizmoVisitor(None);
-    event.record(&mut visitor);
-    visitor.0
+/// A gizmo field sent from [`GizmoLayer`] to [`render_gizmo_log_events`].
+struct ReceivedGizmo {
+    result: Result<LoggedGizmo, GizmoFieldError>,
+    /// Metadata of the event, to report errors with the callsite.
+    metadata: &'static Metadata<'static>,
+    /// Names of the spans the event was logged in, from outermost to innermost.
+    spans: Vec<&'static str>,
+    /// The fixed timestep tick the event was logged in, if it is attributed to one.
+    tick: Option<u64>,
 }
 
-/// Bevy non-send resource that receives gizmo log events.
-pub struct GizmoLogEventReceiver(mpsc::Receiver<GizmoCommand>);
+/// Bevy resource that receives gizmo log events.
+///
+/// Gizmos can be logged from any thread, and [`render_gizmo_log_events`]
+/// can run on any thread, in parallel with systems that don't draw gizmos.
+#[derive(Resource)]
+pub struct GizmoLogEventReceiver(Arc<GizmoQueue<ReceivedGizmo>>);
+
+fn advance_gizmo_queue_frame(receiver: Res<GizmoLogEventReceiver>) {
+    receiver.0.advance_frame();
+}
 
 /// Bevy system that ultimately renders the gizmos.
 ///
@@ -76,9 +177,289 @@ pub struct GizmoLogEventReceiver(mpsc::Receiver<GizmoCommand>);
 ///
 /// By default, runs in [`PostUpdate`].
 ///
+/// Gizmos are drawn in [`LogGizmoConfigGroup`], unless they are routed to
+/// another config group with [`AppGizmoLogBuilder`], and styled by the level
+/// they were logged at according to [`GizmoLevelStyles`].
+///
+/// Gizmos logged with a `gizmo.entity` field are drawn relative to the
+/// [`GlobalTransform`] of that entity every frame, and aren't drawn while it
+/// has none. Persistent and keyed gizmos are dropped once it is despawned.
+///
+/// Gizmos logged with a `gizmo.space` field of `"viewport"` or `"pixels"` are
+/// converted to world space through the active camera with the highest order,
+/// and aren't drawn while there is none.
+///
+/// Gizmos attributed to fixed timestep ticks with
+/// [`GizmoLayer::with_fixed_ticks`] are drawn until the next tick completes.
+///
+/// Gizmos that were logged by a newer version of bevy_gizmo_log and
+/// can't be drawn are reported with a warning, once per kind of gizmo or
+/// format version.
+/// So are gizmos dropped because the queue of [`GizmoLayer`] was full,
+/// once per app.
+/// Other problems are only reported if [`GizmoLogDiagnosticsPlugin`] is added.
+///
 /// [`PostUpdate`]: bevy_app::PostUpdate
-pub fn render_gizmo_log_events(receiver: NonSend<GizmoLogEventReceiver>, mut gizmos: Gizmos) {
-    for gizmo_command in receiver.0.try_iter() {
-        gizmo_command.draw(&mut gizmos);
+/// [`AppGizmoLogBuilder`]: crate::AppGizmoLogBuilder
+/// [`GizmoLevelStyles`]: crate::GizmoLevelStyles
+/// [`GizmoLogDiagnosticsPlugin`]: crate::GizmoLogDiagnosticsPlugin
+#[allow(clippy::too_many_arguments)]
+pub fn render_gizmo_log_events(
+    receiver: Res<GizmoLogEventReceiver>,
+    mut retained: ResMut<RetainedGizmos>,
+    mut routes: ResMut<GizmoLogRoutes>,
+    styles: Res<GizmoLevelStyles>,
+    time: Option<Res<Time>>,
+    transforms: Query<Option<&GlobalTransform>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut gizmos: Gizmos<LogGizmoConfigGroup>,
+    mut stats: Option<ResMut<GizmoLogStats>>,
+    mut reported_variants: Local<HashSet<String>>,
+    mut reported_overflow: Local<bool>,
+    ticks: Res<FixedTicks>,
+    mut tick_gizmos: Local<TickGizmos>,
+) {
+    let camera = active_camera(&cameras);
+    let mut parsed = 0;
+    let mut rendered = 0;
+    let overflowed = receiver.0.take_dropped();
+    if overflowed > 0 && !*reported_overflow {
+        *reported_overflow = true;
+        tracing::warn!(
+            "Dropped {overflowed} gizmo logs because they were logged faster than they were drawn"
+        );
+    }
+    let last_tick = ticks.last_completed();
+    tick_gizmos.prune(last_tick);
+    for received in receiver.0.drain() {
+        match received.result {
+            Ok(logged_gizmo) => {
+                parsed += 1;
+                let level = *received.metadata.level();
+                let group =
+                    routes.resolve(logged_gizmo.group.as_deref(), received.metadata.target());
+                let entity = logged_gizmo.entity.map(|bits| {
+                    // Invalid bits never match an entity, so the gizmo isn't drawn.
+                    Entity::try_from_bits(bits).unwrap_or(Entity::PLACEHOLDER)
+                });
+                let source = GizmoSource {
+                    metadata: received.metadata,
+                    spans: received.spans,
+                };
+                let Some(gizmo) =
+                    retained.handle(logged_gizmo, group, Some(level), entity, Some(source))
+                else {
+                    continue;
+                };
+                match received.tick {
+                    None => {
+                        if draw_placed(
+                            &mut routes,
+                            &styles,
+                            &mut gizmos,
+                            &transforms,
+                            camera,
+                            &gizmo,
+                        ) {
+                            rendered += 1;
+                        }
+                    }
+                    Some(tick) => tick_gizmos.push(tick, gizmo, last_tick),
+                }
+            }
+            Err(err) => match stats {
+                Some(ref mut stats) => stats.drop_gizmo(&err, received.metadata),
+                None => {
+                    let newer = match &err {
+                        GizmoFieldError::Decode(DecodeError::UnknownVariant {
+                            variant, ..
+                        }) => Some(variant.clone()),
+                        GizmoFieldError::Decode(DecodeError::UnsupportedVersion { version }) => {
+                            Some(format!("v{version}"))
+                        }
+                        _ => None,
+                    };
+                    if newer.is_some_and(|newer| reported_variants.insert(newer)) {
+                        tracing::warn!("{err}");
+                    }
+                }
+            },
+        }
+    }
+    retained.remove_detached(|entity| transforms.contains(entity));
+    retained.draw(|gizmo| {
+        if draw_placed(
+            &mut routes,
+            &styles,
+            &mut gizmos,
+            &transforms,
+            camera,
+            gizmo,
+        ) {
+            rendered += 1;
+        }
+    });
+    for gizmo in tick_gizmos.of_tick(last_tick) {
+        if draw_placed(
+            &mut routes,
+            &styles,
+            &mut gizmos,
+            &transforms,
+            camera,
+            gizmo,
+        ) {
+            rendered += 1;
+        }
+    }
+    if let Some(ref mut stats) = stats {
+        stats.parsed += parsed;
+        stats.dropped += overflowed;
+        stats.rendered += rendered;
+    }
+    retained.tick(time.map_or(0., |time| time.delta_secs()), 1);
+}
+
+/// Draw `gizmo` in its config group, relative to its entity if it is attached
+/// to one, converted from its space through `camera`, and styled by its level.
+///
+/// Returns false if the gizmo wasn't drawn.
+fn draw_placed(
+    routes: &mut GizmoLogRoutes,
+    styles: &GizmoLevelStyles,
+    gizmos: &mut Gizmos<LogGizmoConfigGroup>,
+    transforms: &Query<Option<&GlobalTransform>>,
+    camera: Option<(&Camera, &GlobalTransform)>,
+    gizmo: &PlacedGizmo,
+) -> bool {
+    let style = gizmo.level.map(|level| (level, styles.get(level)));
+    if style.is_some_and(|(_, style)| style.hidden) {
+        return false;
+    }
+    let command = match gizmo.entity {
+        None => Cow::Borrowed(&gizmo.command),
+        Some(entity) => match transforms.get(entity) {
+            Ok(Some(transform)) => Cow::Owned(GizmoCommand::Transformed {
+                transform: transform.compute_matrix(),
+                gizmo: Box::new(gizmo.command.clone()),
+            }),
+            _ => return false,
+        },
+    };
+    let Some(mut command) = gizmo.space.to_world(command, camera) else {
+        return false;
+    };
+    let mut group = gizmo.group;
+    if let Some((level, style)) = style {
+        if style.changes_colors() {
+            style.apply_colors(command.to_mut());
+        }
+        if group.is_none() && style.overrides_config() {
+            group = Some(routes.level_group(level));
+        }
+    }
+    routes.draw(gizmos, &command, group);
+    #[cfg(feature = "labels")]
+    routes.queue_labels(&command, gizmo.message.as_deref());
+    #[cfg(feature = "labels")]
+    routes.inspect(&command, gizmo);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{FixedFirst, FixedLast, Update};
+    use bevy_color::palettes::css::RED;
+    use bevy_ecs::system::{IntoSystem, RunSystemOnce, System};
+    use bevy_math::Vec3;
+    use tracing::Dispatch;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::gizmo::typed::line;
+
+    #[test]
+    fn test_log_from_threads() {
+        let mut app = App::new();
+        app.init_resource::<GizmoLogStats>();
+        let dispatch = Dispatch::new(Registry::default().with(GizmoLayer::new(&mut app, Update)));
+
+        let mut system = IntoSystem::into_system(render_gizmo_log_events);
+        system.initialize(app.world_mut());
+        assert!(system.is_send());
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        for _ in 0..10 {
+                            tracing::debug!(gizmo = %line(Vec3::ZERO, Vec3::X, RED));
+                        }
+                    });
+                });
+            }
+        });
+        app.world_mut()
+            .run_system_once(render_gizmo_log_events)
+            .unwrap();
+        assert_eq!(app.world().resource::<GizmoLogStats>().parsed, 40);
+    }
+
+    #[test]
+    fn test_fixed_ticks() {
+        let mut app = App::new();
+        app.init_resource::<GizmoLogStats>();
+        let layer = GizmoLayer::new(&mut app, Update).with_fixed_ticks(true);
+        let dispatch = Dispatch::new(Registry::default().with(layer));
+        let tick = |app: &mut App, gizmos: usize| {
+            app.world_mut().run_schedule(FixedFirst);
+            tracing::dispatcher::with_default(&dispatch, || {
+                for _ in 0..gizmos {
+                    tracing::debug!(gizmo = %line(Vec3::ZERO, Vec3::X, RED));
+                }
+            });
+            app.world_mut().run_schedule(FixedLast);
+        };
+        // Registered, since the gizmos of the last tick are kept in a `Local`.
+        let render = app.register_system(render_gizmo_log_events);
+        let rendered = |app: &mut App| {
+            app.world_mut().run_system(render).unwrap();
+            std::mem::take(&mut app.world_mut().resource_mut::<GizmoLogStats>().rendered)
+        };
+
+        tick(&mut app, 1);
+        tick(&mut app, 2);
+        assert_eq!(rendered(&mut app), 2);
+        // Redrawn in frames without ticks.
+        assert_eq!(rendered(&mut app), 2);
+        tick(&mut app, 0);
+        assert_eq!(rendered(&mut app), 0);
+    }
+
+    #[test]
+    fn test_log_from_other_thread_during_tick() {
+        let mut app = App::new();
+        app.init_resource::<GizmoLogStats>();
+        let layer = GizmoLayer::new(&mut app, Update).with_fixed_ticks(true);
+        let dispatch = Dispatch::new(Registry::default().with(layer));
+        let log = || {
+            tracing::dispatcher::with_default(&dispatch, || {
+                tracing::debug!(gizmo = %line(Vec3::ZERO, Vec3::X, RED));
+            });
+        };
+        app.world_mut().run_schedule(FixedFirst);
+        log();
+        std::thread::scope(|scope| {
+            scope.spawn(log);
+        });
+        app.world_mut().run_schedule(FixedLast);
+
+        let render = app.register_system(render_gizmo_log_events);
+        let rendered = |app: &mut App| {
+            app.world_mut().run_system(render).unwrap();
+            std::mem::take(&mut app.world_mut().resource_mut::<GizmoLogStats>().rendered)
+        };
+        // The gizmo of the other thread is drawn once, not kept with the tick.
+        assert_eq!(rendered(&mut app), 2);
+        assert_eq!(rendered(&mut app), 1);
     }
 }
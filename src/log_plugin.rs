@@ -1,10 +1,23 @@
+use std::path::PathBuf;
+
 use bevy_app::{App, Plugin, PostUpdate};
 use bevy_log::{BoxedLayer, Level};
 use tracing::{self, Subscriber};
 use tracing_log::LogTracer;
-use tracing_subscriber::{filter::FilterFn, fmt, layer::SubscriberExt, EnvFilter, Layer, Registry};
+use tracing_subscriber::{
+    filter::{FilterExt, FilterFn},
+    fmt,
+    layer::SubscriberExt,
+    EnvFilter, Layer, Registry,
+};
 
-use crate::log_layer::GizmoLayer;
+#[cfg(feature = "labels")]
+use crate::labels::GizmoLabelSettings;
+use crate::{
+    diagnostics::GizmoLogDiagnosticsPlugin, filter::GizmoFilter, level_style::GizmoLevelStyles,
+    log_layer::GizmoLayer, queue::GizmoOverflow, recorder::GizmoRecorder,
+    ticks::run_fixed_schedules_on_tick_thread,
+};
 
 /// Replacement for bevy's [`LogPlugin`] that handles gizmo logs.
 ///
@@ -36,19 +49,55 @@ pub struct GizmoLogPlugin {
     // GizmoLayer to receive gizmo logs so that they can
     // be rendered, but we don't want to pollute stderr with gizmzo
     // logs every frame.
+    /// Filters logs written to stderr and passed to `custom_layer`, like
+    /// [`LogPlugin::filter`]. Overridden by the `RUST_LOG` environment variable.
+    ///
+    /// [`LogPlugin::filter`]: bevy_log::LogPlugin::filter
     pub filter: String,
     pub level: Level,
     pub custom_layer: fn(app: &mut App) -> Option<BoxedLayer>,
+    /// The [`EnvFilter`] directives that choose which gizmo logs are drawn
+    /// and recorded, independently from `filter` and `level`.
+    /// They can be changed at runtime with the [`GizmoFilter`] resource.
+    /// If they are invalid, an error is logged and the default
+    /// `"[{gizmo}]=debug"` is used.
+    pub gizmo_filter: String,
+    /// If set, every gizmo log is also written to this file by a [`GizmoRecorder`].
+    pub record_to: Option<PathBuf>,
+    /// If true, adds [`GizmoLogDiagnosticsPlugin`] to report gizmo logs that can't be drawn.
+    pub diagnostics: bool,
+    /// How many gizmo logs can wait to be drawn, see [`GizmoLayer::with_capacity`].
+    pub queue_capacity: usize,
+    /// What happens to gizmo logs past `queue_capacity`.
+    pub queue_overflow: GizmoOverflow,
+    /// If true, gizmos logged in `FixedUpdate` are drawn until the next fixed
+    /// timestep tick, see [`GizmoLayer::with_fixed_ticks`]. This runs
+    /// `FixedPreUpdate`, `FixedUpdate` and `FixedPostUpdate` with the
+    /// single-threaded executor, so that their gizmos are attributed to ticks.
+    pub fixed_ticks: bool,
+    /// How gizmos are styled by the level they are logged at.
+    pub level_styles: GizmoLevelStyles,
+    /// How labels are drawn, and whether log messages are drawn as labels.
+    #[cfg(feature = "labels")]
+    pub labels: GizmoLabelSettings,
 }
 
 impl Default for GizmoLogPlugin {
     fn default() -> Self {
         Self {
-            // Same default as bevy's LogPlugin plus an exception
-            // for gizmo fields
-            filter: "wgpu=error,naga=warn,[{gizmo}]=debug".to_owned(),
+            // Same default as bevy's LogPlugin
+            filter: "wgpu=error,naga=warn".to_owned(),
             level: Level::INFO,
             custom_layer: |_| None,
+            gizmo_filter: "[{gizmo}]=debug".to_owned(),
+            record_to: None,
+            diagnostics: false,
+            queue_capacity: GizmoLayer::DEFAULT_CAPACITY,
+            queue_overflow: GizmoOverflow::default(),
+            fixed_ticks: false,
+            level_styles: GizmoLevelStyles::default(),
+            #[cfg(feature = "labels")]
+            labels: GizmoLabelSettings::default(),
         }
     }
 }
@@ -60,21 +109,71 @@ impl Plugin for GizmoLogPlugin {
         let to_stderr_layer = fmt::Layer::default().with_writer(std::io::stderr);
         // Filter out gizmo logs from stderr
         // TODO: filter by log level, not by field?
-        let to_stderr_layer = to_stderr_layer
-            .with_filter(FilterFn::new(|meta| meta.fields().field("gizmo").is_none()));
+        let to_stderr_layer = to_stderr_layer.with_filter(
+            self.env_filter()
+                .and(FilterFn::new(|meta| meta.fields().field("gizmo").is_none())),
+        );
+
+        let recorder = self
+            .record_to
+            .as_ref()
+            .map(|path| GizmoRecorder::new(app, path).map_err(|err| (path, err)))
+            .transpose();
+        let (recorder, recorder_error) = match recorder {
+            Ok(recorder) => (recorder, None),
+            Err(err) => (None, Some(err)),
+        };
 
+        let subscriber = subscriber.with((self.custom_layer)(app).with_filter(self.env_filter()));
+        let default_gizmo_filter = GizmoLogPlugin::default().gizmo_filter;
+        let (gizmo_filter, gizmo_filter_error) =
+            match GizmoFilter::reloadable(app, &self.gizmo_filter) {
+                Ok(filter) => (filter, None),
+                Err(err) => {
+                    let filter = GizmoFilter::reloadable(app, &default_gizmo_filter)
+                        .expect("the default gizmo filter is valid");
+                    (filter, Some(err))
+                }
+            };
         let subscriber = subscriber
-            .with((self.custom_layer)(app))
-            .with(GizmoLayer::new(app, PostUpdate))
-            .with(self.global_filter_layer())
+            .with(
+                GizmoLayer::new(app, PostUpdate)
+                    .with_capacity(self.queue_capacity, self.queue_overflow)
+                    .with_fixed_ticks(self.fixed_ticks)
+                    .and_then(recorder)
+                    .with_filter(gizmo_filter),
+            )
             .with(to_stderr_layer);
 
         Self::set_global_subscriber(subscriber);
+
+        if self.fixed_ticks {
+            run_fixed_schedules_on_tick_thread(app);
+        }
+
+        app.insert_resource(self.level_styles.clone());
+        #[cfg(feature = "labels")]
+        app.insert_resource(self.labels.clone());
+
+        if self.diagnostics {
+            app.add_plugins(GizmoLogDiagnosticsPlugin);
+        }
+
+        if let Some((path, err)) = recorder_error {
+            tracing::error!("Could not record gizmos to {}: {err}", path.display());
+        }
+
+        if let Some(err) = gizmo_filter_error {
+            tracing::error!(
+                "Could not parse gizmo filter {:?}, using {default_gizmo_filter:?} instead: {err}",
+                self.gizmo_filter
+            );
+        }
     }
 }
 
 impl GizmoLogPlugin {
-    fn global_filter_layer(&self) -> EnvFilter {
+    fn env_filter(&self) -> EnvFilter {
         let default_filter = { format!("{},{}", self.level, self.filter) };
         EnvFilter::try_from_default_env()
             .or_else(|_| EnvFilter::try_new(&default_filter))
@@ -0,0 +1,135 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+/// What [`GizmoLayer`] does with new gizmo logs while its queue is full.
+///
+/// The queue fills up when [`render_gizmo_log_events`] doesn't run, for
+/// example while its schedule isn't run in a headless test, or when a loop
+/// logs gizmos faster than they are drawn. Dropped gizmos are counted by
+/// [`GizmoLogDiagnosticsPlugin::DROPPED`].
+///
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`render_gizmo_log_events`]: crate::render_gizmo_log_events
+/// [`GizmoLogDiagnosticsPlugin::DROPPED`]: crate::GizmoLogDiagnosticsPlugin::DROPPED
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GizmoOverflow {
+    /// Drop the oldest queued gizmos to make room for new ones.
+    #[default]
+    DropOldest,
+    /// Drop new gizmos until there is room again.
+    DropNewest,
+    /// Like [`DropOldest`](Self::DropOldest), and also drop the gizmos of
+    /// earlier frames that weren't drawn, so that only the gizmos of the
+    /// last frame are kept while [`render_gizmo_log_events`] doesn't run.
+    ///
+    /// Frames are counted in bevy's `First` schedule.
+    ///
+    /// [`render_gizmo_log_events`]: crate::render_gizmo_log_events
+    LastFrameOnly,
+}
+
+/// A bounded queue of gizmo logs, filled by the layer and drained by the renderer.
+pub(crate) struct GizmoQueue<T> {
+    state: Mutex<QueueState<T>>,
+}
+
+struct QueueState<T> {
+    /// Items with the frame they were pushed in.
+    items: VecDeque<(u64, T)>,
+    capacity: usize,
+    overflow: GizmoOverflow,
+    frame: u64,
+    /// Items dropped since the last call to `take_dropped`.
+    dropped: u64,
+}
+
+impl<T> GizmoQueue<T> {
+    pub(crate) fn new(capacity: usize, overflow: GizmoOverflow) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                items: VecDeque::new(),
+                capacity,
+                overflow,
+                frame: 0,
+                dropped: 0,
+            }),
+        }
+    }
+
+    /// Change the capacity and overflow policy, dropping items if there are too many.
+    pub(crate) fn configure(&self, capacity: usize, overflow: GizmoOverflow) {
+        let mut state = self.state.lock().unwrap();
+        state.capacity = capacity;
+        state.overflow = overflow;
+        let excess = state.items.len().saturating_sub(capacity);
+        state.items.drain(..excess);
+        state.dropped += excess as u64;
+    }
+
+    pub(crate) fn push(&self, item: T) {
+        let mut state = self.state.lock().unwrap();
+        if state.items.len() >= state.capacity {
+            state.dropped += 1;
+            if state.overflow == GizmoOverflow::DropNewest || state.capacity == 0 {
+                return;
+            }
+            state.items.pop_front();
+        }
+        let frame = state.frame;
+        state.items.push_back((frame, item));
+    }
+
+    /// Remove every queued item.
+    pub(crate) fn drain(&self) -> impl Iterator<Item = T> {
+        let items = std::mem::take(&mut self.state.lock().unwrap().items);
+        items.into_iter().map(|(_, item)| item)
+    }
+
+    /// Start a new frame. With [`GizmoOverflow::LastFrameOnly`], drop the
+    /// items that are older than the frame that just ended.
+    pub(crate) fn advance_frame(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.frame += 1;
+        if state.overflow == GizmoOverflow::LastFrameOnly {
+            let last_frame = state.frame - 1;
+            let stale = state
+                .items
+                .iter()
+                .take_while(|(frame, _)| *frame < last_frame)
+                .count();
+            state.items.drain(..stale);
+            state.dropped += stale as u64;
+        }
+    }
+
+    /// The number of items dropped since the last call.
+    pub(crate) fn take_dropped(&self) -> u64 {
+        std::mem::take(&mut self.state.lock().unwrap().dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow() {
+        let queue = GizmoQueue::new(3, GizmoOverflow::DropOldest);
+        (0..5).for_each(|i| queue.push(i));
+        assert_eq!(queue.drain().collect::<Vec<_>>(), [2, 3, 4]);
+        assert_eq!(queue.take_dropped(), 2);
+
+        queue.configure(3, GizmoOverflow::DropNewest);
+        (0..5).for_each(|i| queue.push(i));
+        assert_eq!(queue.drain().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(queue.take_dropped(), 2);
+
+        queue.configure(3, GizmoOverflow::LastFrameOnly);
+        queue.push(0);
+        queue.advance_frame();
+        queue.push(1);
+        queue.advance_frame();
+        queue.push(2);
+        assert_eq!(queue.drain().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(queue.take_dropped(), 1);
+    }
+}
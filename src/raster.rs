@@ -0,0 +1,436 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use bevy_color::{Alpha, Color, ColorToComponents, ColorToPacked, LinearRgba, Mix, Srgba};
+use bevy_math::{Mat4, Vec2, Vec3, Vec4};
+
+use crate::{
+    gizmo::{
+        wire::{self, DecodeError},
+        GizmoCommand, GizmoValue,
+    },
+    record::GizmoRecord,
+    tessellate::Lines,
+};
+
+/// Environment variable that makes [`GizmoImage::compare_golden`] overwrite
+/// golden images instead of comparing against them.
+pub const UPDATE_GOLDEN_VAR: &str = "GIZMO_UPDATE_GOLDEN";
+
+/// A CPU rasterizer that draws gizmo logs into an RGBA image, without bevy.
+///
+/// By default, the image is viewed like bevy's default 2D camera: one world unit
+/// is one pixel, the origin is in the center of the image and y points up.
+/// 3D gizmos can be drawn by supplying a view-projection matrix with
+/// [`with_view_projection`](Self::with_view_projection).
+///
+/// Lines are one pixel wide and drawn in order, without depth testing.
+///
+/// ```
+/// # use bevy_color::palettes::css::{BLACK, RED};
+/// # use bevy_math::Vec2;
+/// use bevy_gizmo_log::{gizmo::circle_2d, GizmoImage};
+///
+/// let mut image = GizmoImage::new(64, 64).with_background(BLACK);
+/// image.draw_str(&circle_2d(Vec2::ZERO, 20.0, RED)).unwrap();
+/// assert_eq!(image.pixel(32, 12), Some(RED));
+/// ```
+pub struct GizmoImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<LinearRgba>,
+    view_projection: Mat4,
+}
+
+impl GizmoImage {
+    /// Create a transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        let (half_width, half_height) = (width as f32 / 2.0, height as f32 / 2.0);
+        Self {
+            width,
+            height,
+            pixels: vec![LinearRgba::NONE; width as usize * height as usize],
+            view_projection: Mat4::orthographic_rh(
+                -half_width,
+                half_width,
+                -half_height,
+                half_height,
+                -1000.0,
+                1000.0,
+            ),
+        }
+    }
+
+    /// Fill the image with a color.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.pixels.fill(color.into().into());
+        self
+    }
+
+    /// Set the matrix that transforms world space into clip space,
+    /// i.e. the camera's projection matrix times the inverse of its transform.
+    pub fn with_view_projection(mut self, view_projection: Mat4) -> Self {
+        self.view_projection = view_projection;
+        self
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The color of a pixel, counting from the top left corner.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Srgba> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let pixel = self.pixels[(y * self.width + x) as usize];
+        Some(Srgba::from_u8_array(Srgba::from(pixel).to_u8_array()))
+    }
+
+    /// Draw a gizmo string, as returned by the functions in [`gizmo`](crate::gizmo).
+    pub fn draw_str(&mut self, gizmo: &str) -> Result<(), DecodeError> {
+        let command = wire::decode(gizmo)?;
+        self.draw_command(&command);
+        Ok(())
+    }
+
+    /// Draw a typed gizmo.
+    pub fn draw_value(&mut self, gizmo: &GizmoValue) {
+        self.draw_command(&gizmo.0);
+    }
+
+    /// Draw the gizmo of a recorded event.
+    pub fn draw_record(&mut self, record: &GizmoRecord) -> Result<(), DecodeError> {
+        self.draw_command(&record.command()?);
+        Ok(())
+    }
+
+    /// Draw every record that was logged in `frame`.
+    ///
+    /// Only the gizmos logged in that frame are drawn. Persistent and keyed
+    /// gizmos logged in earlier frames are not.
+    pub fn draw_frame<'a>(
+        &mut self,
+        records: impl IntoIterator<Item = &'a GizmoRecord>,
+        frame: u64,
+    ) -> Result<(), DecodeError> {
+        for record in records {
+            if record.frame == frame {
+                self.draw_record(record)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_command(&mut self, command: &GizmoCommand) {
+        let mut lines = Lines::default();
+        lines.add(command);
+        for segment in lines.segments {
+            self.draw_line(
+                segment.start,
+                segment.end,
+                segment.start_color.into(),
+                segment.end_color.into(),
+            );
+        }
+    }
+
+    fn draw_line(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: LinearRgba,
+        end_color: LinearRgba,
+    ) {
+        // Clip against the near plane before dividing by w.
+        const MIN_W: f32 = 1e-6;
+        let mut start = self.view_projection * start.extend(1.0);
+        let mut end = self.view_projection * end.extend(1.0);
+        let (mut t0, mut t1) = (0.0, 1.0);
+        if start.w < MIN_W && end.w < MIN_W {
+            return;
+        } else if start.w < MIN_W {
+            t0 = (MIN_W - start.w) / (end.w - start.w);
+        } else if end.w < MIN_W {
+            t1 = (MIN_W - start.w) / (end.w - start.w);
+        }
+        (start, end) = (start.lerp(end, t0), start.lerp(end, t1));
+
+        let start_px = self.clip_to_pixel(start);
+        let end_px = self.clip_to_pixel(end);
+        let Some((s0, s1)) = clip_to_rect(start_px, end_px, self.size()) else {
+            return;
+        };
+
+        let delta = end_px - start_px;
+        let steps = (delta * (s1 - s0)).abs().max_element().ceil().max(1.0) as u32;
+        for step in 0..=steps {
+            let s = s0 + (s1 - s0) * step as f32 / steps as f32;
+            let position = start_px + delta * s;
+            let color = start_color.mix(&end_color, t0 + (t1 - t0) * s);
+            self.blend(position, color);
+        }
+    }
+
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    fn clip_to_pixel(&self, clip: Vec4) -> Vec2 {
+        let ndc = clip.truncate().truncate() / clip.w;
+        Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * self.size()
+    }
+
+    /// Draw `color` over the pixel at `position`.
+    fn blend(&mut self, position: Vec2, color: LinearRgba) {
+        let (x, y) = (position.x.floor(), position.y.floor());
+        if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+            return;
+        }
+        let pixel = &mut self.pixels[y as usize * self.width as usize + x as usize];
+        let alpha = color.alpha + pixel.alpha * (1.0 - color.alpha);
+        if alpha <= 0.0 {
+            return;
+        }
+        let rgb = (color.to_vec3() * color.alpha
+            + pixel.to_vec3() * pixel.alpha * (1.0 - color.alpha))
+            / alpha;
+        *pixel = LinearRgba::from_vec3(rgb).with_alpha(alpha);
+    }
+
+    /// The image as 8-bit sRGB RGBA bytes, row by row from the top.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&pixel| Srgba::from(pixel).to_u8_array())
+            .collect()
+    }
+
+    /// Encode the image as a PNG file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), png::EncodingError> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_rgba8())?;
+        writer.finish()
+    }
+
+    /// Compare the image with a golden PNG file.
+    ///
+    /// The images match if no channel of any pixel differs by more than `tolerance`.
+    /// If they don't match, or the golden image doesn't exist, the image is saved
+    /// next to it with the extension `.actual.png` so that it can be inspected.
+    ///
+    /// If the [`GIZMO_UPDATE_GOLDEN`](UPDATE_GOLDEN_VAR) environment variable is
+    /// set, the golden image is overwritten instead.
+    ///
+    /// ```no_run
+    /// # use bevy_gizmo_log::GizmoImage;
+    /// let image = GizmoImage::new(64, 64);
+    /// image.compare_golden("tests/golden/empty.png", 0).unwrap();
+    /// ```
+    pub fn compare_golden(&self, path: impl AsRef<Path>, tolerance: u8) -> Result<(), GoldenError> {
+        let path = path.as_ref();
+        if env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+            return self.save(path).map_err(GoldenError::Encode);
+        }
+        let result = self.compare_file(path, tolerance);
+        if result.is_err() {
+            self.save(actual_path(path)).map_err(GoldenError::Encode)?;
+        }
+        result
+    }
+
+    fn compare_file(&self, path: &Path, tolerance: u8) -> Result<(), GoldenError> {
+        let file = File::open(path).map_err(GoldenError::Io)?;
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(GoldenError::Decode)?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or_default()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(GoldenError::Decode)?;
+
+        let expected = (info.width, info.height);
+        let actual = (self.width, self.height);
+        if expected != actual {
+            return Err(GoldenError::SizeMismatch { expected, actual });
+        }
+        let golden = to_rgba8(&buffer[..info.buffer_size()], info.color_type);
+        let pixels = golden
+            .chunks_exact(4)
+            .zip(self.to_rgba8().chunks_exact(4))
+            .filter(|(golden, actual)| {
+                golden
+                    .iter()
+                    .zip(actual.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > tolerance)
+            })
+            .count();
+        if pixels > 0 {
+            return Err(GoldenError::Mismatch { pixels });
+        }
+        Ok(())
+    }
+}
+
+/// The part of the line from `start` to `end` that is inside the rectangle
+/// from zero to `size`, as fractions of the line.
+fn clip_to_rect(start: Vec2, end: Vec2, size: Vec2) -> Option<(f32, f32)> {
+    let delta = end - start;
+    let (mut s0, mut s1) = (0.0f32, 1.0f32);
+    for (p, q) in [
+        (-delta.x, start.x),
+        (delta.x, size.x - start.x),
+        (-delta.y, start.y),
+        (delta.y, size.y - start.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            s0 = s0.max(q / p);
+        } else {
+            s1 = s1.min(q / p);
+        }
+    }
+    (s0 <= s1).then_some((s0, s1))
+}
+
+/// Expand decoded 8-bit pixels into RGBA.
+fn to_rgba8(buffer: &[u8], color_type: png::ColorType) -> Vec<u8> {
+    match color_type {
+        png::ColorType::Rgba => buffer.to_vec(),
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], u8::MAX])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale | png::ColorType::Indexed => {
+            buffer.iter().flat_map(|&p| [p, p, p, u8::MAX]).collect()
+        }
+    }
+}
+
+fn actual_path(path: &Path) -> PathBuf {
+    path.with_extension("actual.png")
+}
+
+/// Error returned by [`GizmoImage::compare_golden`].
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(io::Error),
+    Decode(png::DecodingError),
+    Encode(png::EncodingError),
+    /// The images have different sizes, given as `(width, height)`.
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// The number of pixels that differ by more than the tolerance.
+    Mismatch {
+        pixels: usize,
+    },
+}
+
+impl std::fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(f, "could not read golden image: {err}"),
+            GoldenError::Decode(err) => write!(f, "could not decode golden image: {err}"),
+            GoldenError::Encode(err) => write!(f, "could not save image: {err}"),
+            GoldenError::SizeMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            GoldenError::Mismatch { pixels } => {
+                write!(f, "{pixels} pixels differ from the golden image")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::{BLACK, BLUE, LIME, RED};
+    use bevy_math::{Vec2, Vec3};
+
+    use super::*;
+    use crate::gizmo::{line, line_2d, line_gradient_2d};
+
+    #[test]
+    fn test_draw_2d() {
+        let mut image = GizmoImage::new(8, 8).with_background(BLACK);
+        image
+            .draw_str(&line_2d(Vec2::new(-4.0, 0.5), Vec2::new(4.0, 0.5), RED))
+            .unwrap();
+        image
+            .draw_str(&line_gradient_2d(
+                Vec2::new(-3.5, 4.0),
+                Vec2::new(-3.5, -4.0),
+                LIME,
+                BLUE,
+            ))
+            .unwrap();
+        // y points up, so y = 0.5 is the row above the center.
+        assert_eq!(image.pixel(7, 3), Some(RED));
+        assert_eq!(image.pixel(7, 4), Some(BLACK));
+        assert_eq!(image.pixel(0, 0), Some(LIME));
+        let bottom = image.pixel(0, 7).unwrap();
+        assert!(bottom.blue > bottom.green);
+    }
+
+    #[test]
+    fn test_draw_3d() {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let projection = Mat4::perspective_rh(1.0, 1.0, 0.1, 100.0);
+        let mut image = GizmoImage::new(16, 16).with_view_projection(projection * view);
+        // A line that passes behind the camera is clipped, not wrapped around.
+        image
+            .draw_str(&line(Vec3::NEG_X, Vec3::new(-1.0, 0.0, 20.0), RED))
+            .unwrap();
+        assert_eq!(image.pixel(0, 8), Some(RED));
+        assert_eq!(image.pixel(15, 8).map(|p| p.alpha), Some(0.0));
+    }
+
+    #[test]
+    fn test_compare_golden() {
+        let dir = env::temp_dir().join("bevy_gizmo_log_test_compare_golden");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("line.png");
+        let mut image = GizmoImage::new(8, 8);
+        image
+            .draw_str(&line_2d(Vec2::new(-4.0, 0.5), Vec2::new(4.0, 0.5), RED))
+            .unwrap();
+        image.save(&path).unwrap();
+        image.compare_golden(&path, 0).unwrap();
+
+        image
+            .draw_str(&line_2d(Vec2::new(-4.0, -0.5), Vec2::new(0.0, -0.5), RED))
+            .unwrap();
+        assert!(matches!(
+            image.compare_golden(&path, 0),
+            Err(GoldenError::Mismatch { .. })
+        ));
+        assert!(actual_path(&path).exists());
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::BufRead;
+
+use crate::gizmo::{
+    is_default,
+    wire::{self, DecodeError},
+    GizmoCommand, GizmoKey, GizmoLifetime, GizmoSpace,
+};
+
+/// One gizmo log event, as written to a file by [`GizmoRecorder`].
+///
+/// A recording is a text file containing one [RON](https://github.com/ron-rs/ron)
+/// encoded [`GizmoRecord`] per line. Use [`read_recording`] to read it back.
+///
+/// The gizmo itself is stored in the versioned format of [`wire`], so that
+/// recordings made by newer versions of bevy_gizmo_log can still be read,
+/// and gizmos that can't be decoded are reported as a [`DecodeError`].
+///
+/// [`GizmoRecorder`]: crate::GizmoRecorder
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GizmoRecord {
+    /// The frame in which the gizmo was logged, counting from when recording started.
+    pub frame: u64,
+    /// Seconds since recording started.
+    pub timestamp: f64,
+    /// The level of the log event, e.g. `"DEBUG"`.
+    pub level: String,
+    /// The target of the log event, which defaults to the module path.
+    pub target: String,
+    /// Names of the spans the event was logged in, from outermost to innermost.
+    pub spans: Vec<String>,
+    /// The logged gizmo, as a payload of the [`wire`] format.
+    /// Decode it with [`GizmoRecord::command`].
+    pub gizmo: String,
+    /// The `gizmo.group` field of the event, naming the config group to draw the gizmo in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// The message of the event, if it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub(crate) lifetime: Option<GizmoLifetime>,
+    #[serde(default)]
+    pub(crate) key: Option<GizmoKey>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) space: GizmoSpace,
+}
+
+impl GizmoRecord {
+    /// Encode the record as a single line of RON, without a trailing newline.
+    pub fn to_line(&self) -> String {
+        ron::ser::to_string(self).unwrap()
+    }
+
+    /// Decode a record from a single line of RON.
+    pub fn from_line(line: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_str(line)
+    }
+
+    /// Decode the logged gizmo.
+    pub fn command(&self) -> Result<GizmoCommand, DecodeError> {
+        wire::decode(&self.gizmo)
+    }
+
+    /// Decode the logged gizmo along with the options it was logged with.
+    #[cfg_attr(not(feature = "bevy"), allow(dead_code))]
+    pub(crate) fn logged(&self) -> Result<crate::visitor::LoggedGizmo, DecodeError> {
+        Ok(crate::visitor::LoggedGizmo {
+            command: self.command()?,
+            lifetime: self.lifetime,
+            key: self.key.clone(),
+            group: self.group.clone(),
+            entity: None,
+            message: self.message.clone(),
+            space: self.space,
+        })
+    }
+}
+
+/// Read every [`GizmoRecord`] in a recording.
+///
+/// Blank lines are skipped.
+pub fn read_recording(
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<GizmoRecord, RecordingError>> {
+    reader.lines().filter_map(|line| match line {
+        Ok(line) if line.trim().is_empty() => None,
+        Ok(line) => Some(GizmoRecord::from_line(&line).map_err(RecordingError::Parse)),
+        Err(err) => Some(Err(RecordingError::Io(err))),
+    })
+}
+
+/// Error returned by [`read_recording`].
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "could not read recording: {err}"),
+            RecordingError::Parse(err) => write!(f, "could not parse gizmo record: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
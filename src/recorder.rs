@@ -0,0 +1,176 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use bevy_app::{App, First, Last};
+use bevy_ecs::system::{Res, Resource};
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::{frame, gizmo::wire, record::GizmoRecord, visitor::extract_gizmo_command};
+
+/// A [`tracing_subscriber::Layer`] that writes every gizmo log to a file.
+///
+/// Use it alongside [`GizmoLayer`], or set [`GizmoLogPlugin::record_to`].
+/// The recording can be read back with [`read_recording`].
+///
+/// [`tracing_subscriber::Layer`]: tracing_subscriber::Layer
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`GizmoLogPlugin::record_to`]: crate::GizmoLogPlugin::record_to
+/// [`read_recording`]: crate::read_recording
+pub struct GizmoRecorder {
+    shared: Arc<RecorderShared>,
+    start: Instant,
+}
+
+/// State shared between the recorder layer and the bevy systems that drive it.
+struct RecorderShared {
+    frame: AtomicU64,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+#[derive(Resource)]
+struct RecorderState(Arc<RecorderShared>);
+
+impl GizmoRecorder {
+    /// Create a new [`GizmoRecorder`] that writes to the file at `path`,
+    /// and setup `app` to count frames and flush the file every frame.
+    pub fn new(app: &mut App, path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::from_writer(app, BufWriter::new(file)))
+    }
+
+    /// Create a new [`GizmoRecorder`] that writes to `writer`.
+    pub fn from_writer(app: &mut App, writer: impl Write + Send + 'static) -> Self {
+        let shared = Arc::new(RecorderShared {
+            frame: AtomicU64::new(0),
+            writer: Mutex::new(Box::new(writer)),
+        });
+        app.insert_resource(RecorderState(shared.clone()));
+        app.add_systems(First, advance_recorder_frame);
+        app.add_systems(Last, flush_recorder);
+        GizmoRecorder {
+            shared,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for GizmoRecorder {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        frame::on_new_span(attrs, id, &ctx);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        frame::on_record(id, values, &ctx);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(Ok(logged_gizmo)) = extract_gizmo_command(event) else {
+            return;
+        };
+        let metadata = event.metadata();
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name().to_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let record = GizmoRecord {
+            frame: self.shared.frame.load(Ordering::Relaxed),
+            timestamp: self.start.elapsed().as_secs_f64(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_owned(),
+            spans,
+            gizmo: wire::encode(&frame::apply(logged_gizmo.command, event, &ctx)),
+            group: logged_gizmo.group,
+            message: logged_gizmo.message,
+            lifetime: logged_gizmo.lifetime,
+            key: logged_gizmo.key,
+            space: logged_gizmo.space,
+        };
+        if let Ok(mut writer) = self.shared.writer.lock() {
+            let _ = writeln!(writer, "{}", record.to_line());
+        }
+    }
+}
+
+fn advance_recorder_frame(state: Res<RecorderState>) {
+    state.0.frame.fetch_add(1, Ordering::Relaxed);
+}
+
+fn flush_recorder(state: Res<RecorderState>) {
+    if let Ok(mut writer) = state.0.writer.lock() {
+        let _ = writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::RED;
+    use bevy_math::Vec3;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{
+        gizmo::{arrow, GizmoCommand},
+        read_recording,
+    };
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let mut app = App::new();
+        let buffer = SharedBuffer::default();
+        let recorder = GizmoRecorder::from_writer(&mut app, buffer.clone());
+        let subscriber = Registry::default().with(recorder);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("outer").entered();
+            tracing::debug!(
+                gizmo = arrow(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.group = "physics",
+                "contact"
+            );
+            tracing::debug!("not a gizmo");
+        });
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        let records: Vec<_> = read_recording(bytes.as_slice())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, "DEBUG");
+        assert_eq!(records[0].spans, ["outer"]);
+        assert_eq!(records[0].frame, 0);
+        assert_eq!(records[0].group.as_deref(), Some("physics"));
+        assert_eq!(records[0].message.as_deref(), Some("contact"));
+        assert!(records[0].gizmo.starts_with("(v:1,"));
+        assert!(matches!(
+            records[0].command(),
+            Ok(GizmoCommand::Arrow { .. })
+        ));
+    }
+}
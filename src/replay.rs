@@ -0,0 +1,431 @@
+use std::{borrow::Cow, collections::HashMap, fs::File, io::BufReader, path::PathBuf};
+
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Query, ResMut, Resource},
+};
+use bevy_gizmos::{gizmos::Gizmos, AppGizmoBuilder};
+use bevy_render::camera::Camera;
+use bevy_transform::components::GlobalTransform;
+
+use crate::{
+    groups::{GizmoLogRoutes, LogGizmoConfigGroup},
+    record::{read_recording, GizmoRecord, RecordingError},
+    render_gizmo_log_events,
+    retained::{PlacedGizmo, RetainedGizmos},
+    space::active_camera,
+    visitor::LoggedGizmo,
+};
+
+/// Plugin that plays back a recording made by [`GizmoRecorder`].
+///
+/// The playback can be controlled through the [`GizmoReplay`] resource.
+///
+/// If [`GizmoLogPlugin`] or [`GizmoLayer`] is added too, recorded gizmos are
+/// drawn in the config groups they are routed to with [`AppGizmoLogBuilder`].
+///
+/// ```no_run
+/// # use bevy::DefaultPlugins;
+/// # use bevy_app::App;
+/// # use bevy_gizmo_log::GizmoReplayPlugin;
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(GizmoReplayPlugin {
+///         path: "gizmos.ron".into(),
+///     });
+/// ```
+///
+/// [`GizmoRecorder`]: crate::GizmoRecorder
+/// [`GizmoLogPlugin`]: crate::GizmoLogPlugin
+/// [`GizmoLayer`]: crate::GizmoLayer
+/// [`AppGizmoLogBuilder`]: crate::AppGizmoLogBuilder
+pub struct GizmoReplayPlugin {
+    /// Path of the recording to play back.
+    pub path: PathBuf,
+}
+
+impl Plugin for GizmoReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let replay = GizmoReplay::load(&self.path).unwrap_or_else(|err| {
+            tracing::error!(
+                "Could not load gizmo recording {}: {err}",
+                self.path.display()
+            );
+            GizmoReplay::default()
+        });
+        app.insert_resource(replay);
+        app.init_gizmo_group::<LogGizmoConfigGroup>();
+        app.add_systems(PostUpdate, replay_gizmos.before(render_gizmo_log_events));
+    }
+}
+
+/// All the gizmos of one recorded frame.
+struct ReplayFrame {
+    frame: u64,
+    timestamp: f64,
+    /// The decoded gizmos, with an index into [`GizmoReplay::routes`].
+    gizmos: Vec<(LoggedGizmo, usize)>,
+}
+
+/// Bevy resource that holds a gizmo recording and its playback state.
+///
+/// Playback advances by [`speed`](Self::speed) recorded frames
+/// every time the app updates, unless it is [`paused`](Self::paused),
+/// after drawing the gizmos of the current frame. Frames are counted by
+/// their recorded number, so frames in which nothing was logged take as
+/// long to play as the others.
+#[derive(Resource)]
+pub struct GizmoReplay {
+    frames: Vec<ReplayFrame>,
+    /// The recorded frame number being shown.
+    frame: u64,
+    /// The timestamp of the last frame in `frames` that was entered.
+    timestamp: f64,
+    /// Index into `frames` of the first frame that wasn't entered yet.
+    next: usize,
+    /// Fraction of a recorded frame that playback has advanced past `frame`.
+    progress: f32,
+    /// Whether playback is paused.
+    pub paused: bool,
+    /// Recorded frames played per app frame.
+    pub speed: f32,
+    retained: RetainedGizmos,
+    /// Gizmos of the current frame that are only drawn while it is shown.
+    current: Vec<PlacedGizmo>,
+    /// The `gizmo.group` field and target of recorded gizmos, resolved to a
+    /// config group when drawn. The group of replayed gizmos indexes into this.
+    routes: Vec<(Option<String>, String)>,
+}
+
+impl Default for GizmoReplay {
+    fn default() -> Self {
+        Self::from_records(Vec::new())
+    }
+}
+
+impl GizmoReplay {
+    /// Load a recording from a file.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, RecordingError> {
+        let file = File::open(path.into()).map_err(RecordingError::Io)?;
+        let records = read_recording(BufReader::new(file)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_records(records))
+    }
+
+    /// Create a replay from records, which should be ordered by frame.
+    ///
+    /// Records whose gizmo can't be decoded are skipped with a warning.
+    pub fn from_records(records: impl IntoIterator<Item = GizmoRecord>) -> Self {
+        let mut frames: Vec<ReplayFrame> = Vec::new();
+        let mut routes = Vec::new();
+        let mut route_indices = HashMap::new();
+        let mut skipped = 0;
+        let mut first_error = None;
+        for record in records {
+            let logged = match record.logged() {
+                Ok(logged) => logged,
+                Err(err) => {
+                    skipped += 1;
+                    first_error.get_or_insert(err);
+                    continue;
+                }
+            };
+            let route = (record.group, record.target);
+            let route = *route_indices.entry(route.clone()).or_insert_with(|| {
+                routes.push(route);
+                routes.len() - 1
+            });
+            match frames.last_mut() {
+                Some(last) if last.frame == record.frame => last.gizmos.push((logged, route)),
+                _ => frames.push(ReplayFrame {
+                    frame: record.frame,
+                    timestamp: record.timestamp,
+                    gizmos: vec![(logged, route)],
+                }),
+            }
+        }
+        if let Some(err) = first_error {
+            tracing::warn!("Skipped {skipped} recorded gizmos that could not be decoded: {err}");
+        }
+        let mut replay = Self {
+            frames,
+            frame: 0,
+            timestamp: 0.0,
+            next: 0,
+            progress: 0.0,
+            paused: false,
+            speed: 1.0,
+            retained: RetainedGizmos::default(),
+            current: Vec::new(),
+            routes,
+        };
+        replay.seek(0);
+        replay
+    }
+
+    /// Pause playback.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume playback.
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+
+    /// Pause playback and advance by a single recorded frame.
+    pub fn step_frame(&mut self) {
+        self.paused = true;
+        self.progress = 0.0;
+        self.advance(1);
+    }
+
+    /// Pause playback and go back by a single recorded frame.
+    pub fn step_back(&mut self) {
+        self.paused = true;
+        if let Some((first, _)) = self.frame_range() {
+            self.seek(self.frame.saturating_sub(1).max(first));
+        }
+    }
+
+    /// Jump to recorded frame `frame`, within [`frame_range`](Self::frame_range).
+    ///
+    /// Keyed and persistent gizmos logged before `frame` are restored,
+    /// so the result looks the same as if playback had reached `frame` on its own.
+    pub fn seek(&mut self, frame: u64) {
+        self.progress = 0.0;
+        self.retained.clear();
+        self.current.clear();
+        self.next = 0;
+        if let Some(first) = self.frames.first() {
+            self.frame = first.frame;
+            self.timestamp = first.timestamp;
+            self.enter_frames(frame);
+        }
+    }
+
+    /// The recorded frame number being shown, or `None` if the recording is empty.
+    ///
+    /// Frames in which no gizmo was logged are shown too, without gizmos of their own.
+    pub fn current_frame(&self) -> Option<u64> {
+        (!self.frames.is_empty()).then_some(self.frame)
+    }
+
+    /// The first and last recorded frame numbers, or `None` if the recording is empty.
+    pub fn frame_range(&self) -> Option<(u64, u64)> {
+        Some((self.frames.first()?.frame, self.frames.last()?.frame))
+    }
+
+    /// Returns true if playback has reached the last recorded frame.
+    pub fn is_finished(&self) -> bool {
+        self.frames
+            .last()
+            .is_none_or(|last| self.frame >= last.frame)
+    }
+
+    /// Move forward by `frames` recorded frames. Returns false if playback is finished.
+    fn advance(&mut self, frames: u64) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.enter_frames(self.frame.saturating_add(frames));
+        true
+    }
+
+    /// Apply the records of every frame up to `target`, which becomes the
+    /// current frame, ticking lifetimes by the recorded time and frames in between.
+    fn enter_frames(&mut self, target: u64) {
+        let Some(last) = self.frames.last() else {
+            return;
+        };
+        let target = target.clamp(self.frame, last.frame);
+        self.current.clear();
+        while let Some(frame) = self
+            .frames
+            .get(self.next)
+            .filter(|frame| frame.frame <= target)
+        {
+            self.retained.tick(
+                (frame.timestamp - self.timestamp) as f32,
+                frame_delta(self.frame, frame.frame),
+            );
+            self.frame = frame.frame;
+            self.timestamp = frame.timestamp;
+            self.current.clear();
+            for (logged, route) in &frame.gizmos {
+                if let Some(gizmo) =
+                    self.retained
+                        .handle(logged.clone(), Some(*route), None, None, None)
+                {
+                    self.current.push(gizmo);
+                }
+            }
+            self.next += 1;
+        }
+        if self.frame < target {
+            // The target has no gizmos of its own.
+            self.retained.tick(0.0, frame_delta(self.frame, target));
+            self.frame = target;
+            self.current.clear();
+        }
+    }
+
+    fn update(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.progress += self.speed.max(0.0);
+        let frames = self.progress.floor();
+        self.progress -= frames;
+        if frames >= 1.0 && !self.advance(frames as u64) {
+            self.progress = 0.0;
+        }
+    }
+
+    /// Call `draw` with the gizmos of the current frame and the `gizmo.group`
+    /// field and target they were logged with, then advance playback.
+    fn draw_and_advance(&mut self, mut draw: impl FnMut(&PlacedGizmo, &(Option<String>, String))) {
+        let routes = &self.routes;
+        let mut draw = |gizmo: &PlacedGizmo| {
+            if let Some(route) = gizmo.group {
+                draw(gizmo, &routes[route]);
+            }
+        };
+        self.current.iter().for_each(&mut draw);
+        self.retained.draw(draw);
+        self.update();
+    }
+}
+
+/// The number of frames between two recorded frame numbers.
+fn frame_delta(from: u64, to: u64) -> u32 {
+    u32::try_from(to.saturating_sub(from)).unwrap_or(u32::MAX)
+}
+
+/// Bevy system that draws the gizmos of the [`GizmoReplay`] and advances it.
+fn replay_gizmos(
+    mut replay: ResMut<GizmoReplay>,
+    mut routes: Option<ResMut<GizmoLogRoutes>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut gizmos: Gizmos<LogGizmoConfigGroup>,
+) {
+    let camera = active_camera(&cameras);
+    replay.draw_and_advance(|gizmo, (name, target)| {
+        let Some(command) = gizmo.space.to_world(Cow::Borrowed(&gizmo.command), camera) else {
+            return;
+        };
+        match &mut routes {
+            Some(routes) => {
+                let group = routes.resolve(name.as_deref(), target);
+                routes.draw(&mut gizmos, &command, group);
+            }
+            None => command.draw(&mut gizmos),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::Color;
+    use bevy_math::Vec3;
+
+    use super::*;
+    use crate::gizmo::{wire, GizmoCommand, GizmoLifetime};
+
+    fn record(frame: u64, key: Option<&str>) -> GizmoRecord {
+        GizmoRecord {
+            frame,
+            timestamp: frame as f64 / 60.0,
+            level: "DEBUG".to_owned(),
+            target: "test".to_owned(),
+            spans: Vec::new(),
+            gizmo: wire::encode(&GizmoCommand::Line {
+                start: Vec3::ZERO,
+                end: Vec3::ONE,
+                color: Color::WHITE,
+            }),
+            group: None,
+            message: None,
+            lifetime: None,
+            key: key.map(Into::into),
+            space: Default::default(),
+        }
+    }
+
+    /// Draw and advance like the system does, returning the number of gizmos drawn.
+    fn play(replay: &mut GizmoReplay) -> usize {
+        let mut drawn = 0;
+        replay.draw_and_advance(|_, _| drawn += 1);
+        drawn
+    }
+
+    #[test]
+    fn test_playback() {
+        let mut replay = GizmoReplay::from_records([
+            record(1, None),
+            record(1, None),
+            record(2, None),
+            record(5, None),
+        ]);
+        assert_eq!(replay.frame_range(), Some((1, 5)));
+        assert_eq!(replay.current_frame(), Some(1));
+        assert_eq!(replay.current.len(), 2);
+
+        replay.speed = 2.0;
+        replay.update();
+        assert_eq!(replay.current_frame(), Some(3));
+        replay.update();
+        assert_eq!(replay.current_frame(), Some(5));
+        assert!(replay.is_finished());
+
+        replay.step_back();
+        assert!(replay.paused);
+        assert_eq!(replay.current_frame(), Some(4));
+        assert!(replay.current.is_empty());
+    }
+
+    #[test]
+    fn test_first_frame_is_drawn() {
+        let mut replay = GizmoReplay::from_records([record(0, None), record(1, None)]);
+        assert_eq!(play(&mut replay), 1);
+        assert_eq!(replay.current_frame(), Some(1));
+        assert_eq!(play(&mut replay), 1);
+    }
+
+    #[test]
+    fn test_gaps_between_frames() {
+        let mut replay = GizmoReplay::from_records([record(0, None), record(10, None)]);
+        play(&mut replay);
+        assert_eq!(replay.current_frame(), Some(1));
+        replay.step_frame();
+        assert_eq!(replay.current_frame(), Some(2));
+        assert_eq!(play(&mut replay), 0);
+        replay.seek(9);
+        replay.step_frame();
+        assert_eq!(replay.current_frame(), Some(10));
+        assert_eq!(play(&mut replay), 1);
+    }
+
+    #[test]
+    fn test_frame_lifetimes_over_gaps() {
+        let mut persistent = record(0, None);
+        persistent.lifetime = Some(GizmoLifetime::Frames(3));
+        let mut replay = GizmoReplay::from_records([persistent, record(100, None)]);
+        assert_eq!(replay.retained.len(), 1);
+        replay.seek(2);
+        assert_eq!(replay.retained.len(), 1);
+        replay.step_frame();
+        assert!(replay.retained.is_empty());
+        replay.seek(100);
+        assert!(replay.retained.is_empty());
+    }
+
+    #[test]
+    fn test_seek_restores_keyed_gizmos() {
+        let mut replay =
+            GizmoReplay::from_records([record(1, Some("a")), record(2, None), record(3, None)]);
+        replay.seek(3);
+        assert_eq!(replay.current_frame(), Some(3));
+        assert_eq!(replay.retained.len(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use bevy_ecs::{entity::Entity, system::Resource};
+use tracing::{Level, Metadata};
+
+use crate::{
+    gizmo::{GizmoCommand, GizmoKey, GizmoLifetime, GizmoSpace},
+    visitor::LoggedGizmo,
+};
+
+/// Bevy resource that stores gizmos which are drawn for more than one frame.
+///
+/// Gizmos are added to this store by logging them with a
+/// `gizmo.ttl_secs` or `gizmo.ttl_frames` field, or with a `gizmo.key` field.
+/// A keyed gizmo replaces the previous gizmo logged with the same key.
+/// Lifetimes in seconds are counted with bevy's `Time` resource, and don't
+/// run out in apps without one.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_gizmo_log::gizmo::sphere;
+/// # use bevy_log::debug;
+/// # use bevy_math::Vec3;
+/// debug!(gizmo = sphere(Vec3::ZERO, 1.0, RED), gizmo.ttl_secs = 2.0);
+/// debug!(gizmo = sphere(Vec3::ONE, 1.0, RED), gizmo.ttl_frames = 10);
+/// debug!(gizmo = sphere(Vec3::NEG_ONE, 1.0, RED), gizmo.key = "target");
+/// ```
+#[derive(Resource, Default)]
+pub struct RetainedGizmos {
+    persistent: Vec<(PlacedGizmo, GizmoLifetime)>,
+    keyed: HashMap<GizmoKey, (PlacedGizmo, Option<GizmoLifetime>)>,
+}
+
+/// A command along with where and how it is drawn.
+pub(crate) struct PlacedGizmo {
+    pub(crate) command: GizmoCommand,
+    /// An index into [`GizmoLogRoutes`](crate::groups::GizmoLogRoutes), or
+    /// `None` for [`LogGizmoConfigGroup`](crate::LogGizmoConfigGroup).
+    pub(crate) group: Option<usize>,
+    /// The level the gizmo was logged at, which styles it when it is drawn,
+    /// or `None` if it was replayed.
+    pub(crate) level: Option<Level>,
+    /// The entity the command is drawn relative to.
+    pub(crate) entity: Option<Entity>,
+    /// The coordinate space of the command, converted to world space when drawn.
+    pub(crate) space: GizmoSpace,
+    /// The message of the log event, drawn as a label next to the gizmo.
+    #[cfg_attr(not(feature = "labels"), allow(dead_code))]
+    pub(crate) message: Option<String>,
+    /// Where the gizmo was logged, or `None` if it was replayed.
+    #[cfg_attr(not(feature = "labels"), allow(dead_code))]
+    pub(crate) source: Option<GizmoSource>,
+}
+
+/// Where a gizmo was logged, shown by the inspector.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "labels"), allow(dead_code))]
+pub(crate) struct GizmoSource {
+    pub(crate) metadata: &'static Metadata<'static>,
+    /// Names of the spans the event was logged in, from outermost to innermost.
+    pub(crate) spans: Vec<&'static str>,
+}
+
+impl RetainedGizmos {
+    /// Remove every retained gizmo.
+    pub fn clear(&mut self) {
+        self.persistent.clear();
+        self.keyed.clear();
+    }
+
+    /// Remove the gizmo retained under `key`.
+    pub fn remove(&mut self, key: impl Into<GizmoKey>) {
+        self.keyed.remove(&key.into());
+    }
+
+    /// The number of retained gizmos.
+    pub fn len(&self) -> usize {
+        self.persistent.len() + self.keyed.len()
+    }
+
+    /// Returns true if there are no retained gizmos.
+    pub fn is_empty(&self) -> bool {
+        self.persistent.is_empty() && self.keyed.is_empty()
+    }
+
+    /// Retain or clear gizmos as requested by `logged_gizmo`.
+    ///
+    /// Returns the gizmo if it should only be drawn this frame.
+    pub(crate) fn handle(
+        &mut self,
+        logged_gizmo: LoggedGizmo,
+        group: Option<usize>,
+        level: Option<Level>,
+        entity: Option<Entity>,
+        source: Option<GizmoSource>,
+    ) -> Option<PlacedGizmo> {
+        let LoggedGizmo {
+            command,
+            lifetime,
+            key,
+            message,
+            space,
+            ..
+        } = logged_gizmo;
+        let mut command = command;
+        self.clear_nested(&mut command);
+        if let GizmoCommand::Clear { key } = command {
+            match key {
+                Some(key) => self.remove(key),
+                None => self.clear(),
+            }
+            return None;
+        }
+        let gizmo = PlacedGizmo {
+            command,
+            group,
+            level,
+            entity,
+            space,
+            message,
+            source,
+        };
+        match (key, lifetime) {
+            (Some(key), lifetime) => self.insert_keyed(key, gizmo, lifetime),
+            (None, Some(lifetime)) => self.insert(gizmo, lifetime),
+            (None, None) => return Some(gizmo),
+        }
+        None
+    }
+
+    /// Apply and remove the clear commands in a batch, before the rest of
+    /// the batch is drawn or retained.
+    fn clear_nested(&mut self, command: &mut GizmoCommand) {
+        match command {
+            GizmoCommand::Batch { gizmos } => gizmos.retain_mut(|gizmo| match gizmo {
+                GizmoCommand::Clear { key } => {
+                    match key.take() {
+                        Some(key) => self.remove(key),
+                        None => self.clear(),
+                    }
+                    false
+                }
+                gizmo => {
+                    self.clear_nested(gizmo);
+                    true
+                }
+            }),
+            GizmoCommand::Transformed { gizmo, .. } => self.clear_nested(gizmo),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn insert(&mut self, gizmo: PlacedGizmo, lifetime: GizmoLifetime) {
+        self.persistent.push((gizmo, lifetime));
+    }
+
+    pub(crate) fn insert_keyed(
+        &mut self,
+        key: GizmoKey,
+        gizmo: PlacedGizmo,
+        lifetime: Option<GizmoLifetime>,
+    ) {
+        self.keyed.insert(key, (gizmo, lifetime));
+    }
+
+    /// Call `draw` with every retained gizmo.
+    pub(crate) fn draw(&self, mut draw: impl FnMut(&PlacedGizmo)) {
+        let persistent = self.persistent.iter().map(|(gizmo, _)| gizmo);
+        let keyed = self.keyed.values().map(|(gizmo, _)| gizmo);
+        for gizmo in persistent.chain(keyed) {
+            draw(gizmo);
+        }
+    }
+
+    /// Drop the gizmos attached to entities for which `exists` returns false.
+    pub(crate) fn remove_detached(&mut self, exists: impl Fn(Entity) -> bool) {
+        let attached = |gizmo: &PlacedGizmo| gizmo.entity.is_none_or(&exists);
+        self.persistent.retain(|(gizmo, _)| attached(gizmo));
+        self.keyed.retain(|_, (gizmo, _)| attached(gizmo));
+    }
+
+    /// Advance every lifetime by `delta_secs` and `delta_frames`, and drop
+    /// the gizmos that expired.
+    pub(crate) fn tick(&mut self, delta_secs: f32, delta_frames: u32) {
+        self.persistent
+            .retain_mut(|(_, lifetime)| lifetime.tick(delta_secs, delta_frames));
+        self.keyed.retain(|_, (_, lifetime)| match lifetime {
+            Some(lifetime) => lifetime.tick(delta_secs, delta_frames),
+            None => true,
+        });
+    }
+}
+
+impl GizmoLifetime {
+    /// Advance the lifetime. Returns false once it has expired.
+    fn tick(&mut self, delta_secs: f32, delta_frames: u32) -> bool {
+        match self {
+            GizmoLifetime::Seconds(secs) => {
+                *secs -= delta_secs;
+                *secs > 0.0
+            }
+            GizmoLifetime::Frames(frames) => {
+                *frames = frames.saturating_sub(delta_frames);
+                *frames > 0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, Update};
+    use bevy_color::{palettes::css::RED, Color};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec3;
+    use bevy_time::Time;
+    use bevy_transform::components::GlobalTransform;
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+    use crate::{
+        gizmo::{line, typed, GizmoBatch},
+        render_gizmo_log_events, GizmoLayer,
+    };
+
+    fn gizmo() -> PlacedGizmo {
+        PlacedGizmo {
+            command: GizmoCommand::Line {
+                start: Vec3::ZERO,
+                end: Vec3::ONE,
+                color: Color::WHITE,
+            },
+            group: None,
+            level: None,
+            entity: None,
+            space: GizmoSpace::World,
+            message: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_frames_expire() {
+        let mut retained = RetainedGizmos::default();
+        retained.insert(gizmo(), GizmoLifetime::Frames(2));
+
+        retained.tick(0.1, 1);
+        assert_eq!(retained.len(), 1);
+        retained.tick(0.1, 1);
+        assert!(retained.is_empty());
+    }
+
+    #[test]
+    fn test_seconds_expire() {
+        let mut retained = RetainedGizmos::default();
+        retained.insert(gizmo(), GizmoLifetime::Seconds(1.0));
+
+        retained.tick(0.6, 1);
+        assert_eq!(retained.len(), 1);
+        retained.tick(0.6, 1);
+        assert!(retained.is_empty());
+    }
+
+    #[test]
+    fn test_keyed_replace() {
+        let mut retained = RetainedGizmos::default();
+        retained.insert_keyed("a".into(), gizmo(), None);
+        retained.insert_keyed("a".into(), gizmo(), None);
+        retained.insert_keyed(GizmoKey::Num(1), gizmo(), None);
+
+        retained.tick(1000.0, 1);
+        assert_eq!(retained.len(), 2);
+        retained.remove("a");
+        assert_eq!(retained.len(), 1);
+    }
+
+    #[test]
+    fn test_drop_detached() {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        let layer = GizmoLayer::new(&mut app, Update);
+        let entity = app.world_mut().spawn(GlobalTransform::IDENTITY).id();
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(
+                gizmo = line(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.entity = entity.to_bits(),
+                gizmo.key = "target"
+            );
+        });
+        let render = |app: &mut App| {
+            app.world_mut()
+                .run_system_once(render_gizmo_log_events)
+                .unwrap();
+            app.world().resource::<RetainedGizmos>().len()
+        };
+        assert_eq!(render(&mut app), 1);
+        app.world_mut().despawn(entity);
+        assert_eq!(render(&mut app), 0);
+    }
+
+    #[test]
+    fn test_clear_from_batch() {
+        let mut app = App::new();
+        let layer = GizmoLayer::new(&mut app, Update);
+        let mut batch = GizmoBatch::new();
+        batch
+            .add(typed::clear("target"))
+            .add(typed::line(Vec3::ZERO, Vec3::ONE, RED));
+
+        tracing::subscriber::with_default(Registry::default().with(layer), || {
+            tracing::debug!(
+                gizmo = line(Vec3::ZERO, Vec3::ONE, RED),
+                gizmo.key = "target"
+            );
+            tracing::debug!(gizmo = %batch, gizmo.key = "batch");
+        });
+        app.world_mut()
+            .run_system_once(render_gizmo_log_events)
+            .unwrap();
+        // The batch is retained without its clear command.
+        let retained = app.world().resource::<RetainedGizmos>();
+        assert_eq!(retained.len(), 1);
+        let (gizmo, _) = &retained.keyed[&GizmoKey::from("batch")];
+        assert!(matches!(&gizmo.command, GizmoCommand::Batch { gizmos } if gizmos.len() == 1));
+    }
+}
@@ -0,0 +1,113 @@
+use std::borrow::Cow;
+
+use bevy_ecs::system::Query;
+use bevy_math::{Mat4, Vec2, Vec3};
+use bevy_render::camera::Camera;
+use bevy_transform::components::GlobalTransform;
+
+use crate::gizmo::{GizmoCommand, GizmoSpace};
+
+/// The depth of gizmos in screen space, in normalized device coordinates.
+///
+/// Bevy uses reversed z, so this is close to the near plane, in front of the scene.
+const SCREEN_DEPTH: f32 = 0.9;
+
+/// The camera that screen space refers to: the active camera with the highest order.
+pub(crate) fn active_camera<'a>(
+    cameras: &'a Query<(&Camera, &GlobalTransform)>,
+) -> Option<(&'a Camera, &'a GlobalTransform)> {
+    cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order)
+}
+
+impl GizmoSpace {
+    /// Convert `command` from this space to world space through `camera`.
+    ///
+    /// Returns `None` if there is no camera to convert through.
+    pub(crate) fn to_world<'a>(
+        self,
+        command: Cow<'a, GizmoCommand>,
+        camera: Option<(&Camera, &GlobalTransform)>,
+    ) -> Option<Cow<'a, GizmoCommand>> {
+        if self == GizmoSpace::World {
+            return Some(command);
+        }
+        let (camera, camera_transform) = camera?;
+        let transform = self.world_from_space(camera.logical_viewport_size()?, |ndc| {
+            camera.ndc_to_world(camera_transform, ndc)
+        })?;
+        Some(Cow::Owned(GizmoCommand::Transformed {
+            transform,
+            gizmo: Box::new(command.into_owned()),
+        }))
+    }
+
+    /// The transform from this space to a plane facing the camera, given the
+    /// size of its viewport and its conversion from normalized device coordinates.
+    fn world_from_space(
+        self,
+        viewport_size: Vec2,
+        ndc_to_world: impl Fn(Vec3) -> Option<Vec3>,
+    ) -> Option<Mat4> {
+        let scale = match self {
+            GizmoSpace::World => return Some(Mat4::IDENTITY),
+            GizmoSpace::Viewport => Vec2::ONE,
+            GizmoSpace::Pixels => viewport_size.recip(),
+        };
+        // Viewport y points down, NDC y points up.
+        let world = |viewport: Vec2| {
+            ndc_to_world(Vec3::new(
+                viewport.x * 2. - 1.,
+                1. - viewport.y * 2.,
+                SCREEN_DEPTH,
+            ))
+        };
+        let origin = world(Vec2::ZERO)?;
+        // Scaled after converting, since one pixel is too small to convert precisely.
+        let x_axis = (world(Vec2::X)? - origin) * scale.x;
+        let y_axis = (world(Vec2::Y)? - origin) * scale.y;
+        // Points towards the camera, with the same scale as x.
+        let z_axis = y_axis.cross(x_axis).normalize_or_zero() * x_axis.length();
+        Some(Mat4::from_cols(
+            x_axis.extend(0.),
+            y_axis.extend(0.),
+            z_axis.extend(0.),
+            origin.extend(1.),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_from_space() {
+        let size = Vec2::new(200., 100.);
+        let ndc_to_world = |ndc: Vec3| Some(ndc * 10.);
+
+        let viewport = GizmoSpace::Viewport
+            .world_from_space(size, ndc_to_world)
+            .unwrap();
+        assert_eq!(
+            viewport.transform_point3(Vec3::ZERO),
+            Vec3::new(-10., 10., 9.)
+        );
+        assert_eq!(
+            viewport.transform_point3(Vec3::new(1., 1., 0.)),
+            Vec3::new(10., -10., 9.)
+        );
+
+        let pixels = GizmoSpace::Pixels
+            .world_from_space(size, ndc_to_world)
+            .unwrap();
+        assert!(pixels
+            .transform_point3(Vec3::new(100., 50., 0.))
+            .abs_diff_eq(Vec3::new(0., 0., 9.), 1e-5));
+        assert!(pixels
+            .transform_point3(Vec3::new(0., 0., 1.))
+            .abs_diff_eq(Vec3::new(-10., 10., 9.1), 1e-5));
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use bevy_color::{Color, ColorToPacked, Srgba};
+use bevy_math::Vec2;
+
+use crate::{
+    gizmo::{
+        wire::{self, DecodeError},
+        GizmoCommand, GizmoValue,
+    },
+    record::{read_recording, GizmoRecord, RecordingError},
+    tessellate::{LineSegment, Lines},
+};
+
+/// Renders 2D gizmo logs into an SVG document, without bevy.
+///
+/// Only 2D gizmos such as [`line_2d`] and [`circle_2d`] can be rendered.
+/// 3D gizmos are counted by [`skipped`](Self::skipped) and otherwise ignored.
+///
+/// ```
+/// # use bevy_color::palettes::css::RED;
+/// # use bevy_math::Vec2;
+/// use bevy_gizmo_log::{gizmo::circle_2d, GizmoSvg};
+///
+/// let mut svg = GizmoSvg::new();
+/// svg.add_str(&circle_2d(Vec2::ZERO, 10.0, RED)).unwrap();
+/// let document = svg.to_svg();
+/// ```
+///
+/// [`line_2d`]: crate::gizmo::line_2d
+/// [`circle_2d`]: crate::gizmo::circle_2d
+pub struct GizmoSvg {
+    lines: Lines,
+    skipped: usize,
+    stroke_width: f32,
+    padding: f32,
+    background: Option<Color>,
+}
+
+impl Default for GizmoSvg {
+    fn default() -> Self {
+        Self {
+            lines: Lines::default(),
+            skipped: 0,
+            stroke_width: 1.0,
+            padding: 8.0,
+            background: None,
+        }
+    }
+}
+
+impl GizmoSvg {
+    /// Create an empty document.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the width of lines in pixels. Defaults to 1.
+    pub fn with_stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Set the space around the drawing in world units. Defaults to 8.
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Fill the document with a background color. Defaults to transparent.
+    pub fn with_background(mut self, color: impl Into<Color>) -> Self {
+        self.background = Some(color.into());
+        self
+    }
+
+    /// Add a gizmo string, as returned by the functions in [`gizmo`](crate::gizmo).
+    pub fn add_str(&mut self, gizmo: &str) -> Result<(), DecodeError> {
+        let command = wire::decode(gizmo)?;
+        self.add_command(&command);
+        Ok(())
+    }
+
+    /// Add a typed gizmo.
+    pub fn add_value(&mut self, gizmo: &GizmoValue) {
+        self.add_command(&gizmo.0);
+    }
+
+    /// Add the gizmo of a recorded event.
+    pub fn add_record(&mut self, record: &GizmoRecord) -> Result<(), DecodeError> {
+        self.add_command(&record.command()?);
+        Ok(())
+    }
+
+    /// Add every gizmo in a recording made by [`GizmoRecorder`].
+    ///
+    /// Recorded gizmos that can't be decoded, such as gizmos logged by a newer
+    /// version of bevy_gizmo_log, are counted by [`skipped`](Self::skipped)
+    /// and reported with a warning.
+    ///
+    /// [`GizmoRecorder`]: crate::GizmoRecorder
+    pub fn add_recording(&mut self, reader: impl BufRead) -> Result<(), RecordingError> {
+        let mut undecodable = 0;
+        let mut first_error = None;
+        for record in read_recording(reader) {
+            if let Err(err) = self.add_record(&record?) {
+                undecodable += 1;
+                first_error.get_or_insert(err);
+            }
+        }
+        self.skipped += undecodable;
+        if let Some(err) = first_error {
+            tracing::warn!(
+                "Skipped {undecodable} recorded gizmos that could not be decoded: {err}"
+            );
+        }
+        Ok(())
+    }
+
+    /// The number of gizmos that were added but can't be rendered to SVG.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    fn add_command(&mut self, command: &GizmoCommand) {
+        if let GizmoCommand::Batch { gizmos } = command {
+            // Only skip the 3D gizmos of a batch.
+            gizmos.iter().for_each(|gizmo| self.add_command(gizmo));
+        } else if command.is_2d() {
+            self.lines.add(command);
+        } else if !matches!(command, GizmoCommand::Clear { .. }) {
+            self.skipped += 1;
+        }
+    }
+
+    /// Write the document to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+
+    /// Render the document.
+    pub fn to_svg(&self) -> String {
+        let segments = &self.lines.segments;
+        let (min, max) = segments
+            .iter()
+            .flat_map(|segment| [segment.start.truncate(), segment.end.truncate()])
+            .fold(None, |bounds: Option<(Vec2, Vec2)>, point| match bounds {
+                Some((min, max)) => Some((min.min(point), max.max(point))),
+                None => Some((point, point)),
+            })
+            .unwrap_or_default();
+        let min = min - self.padding;
+        let size = max + self.padding - min;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+            min.x,
+            flip(min.y + size.y),
+            size.x,
+            size.y,
+            size.x.ceil(),
+            size.y.ceil()
+        );
+        if let Some(background) = self.background {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                min.x,
+                flip(min.y + size.y),
+                size.x,
+                size.y,
+                paint("fill", background)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<g fill="none" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round">"#,
+            self.stroke_width
+        );
+
+        let mut gradients = 0;
+        let mut index = 0;
+        while index < segments.len() {
+            let segment = segments[index];
+            if segment.start_color != segment.end_color {
+                write_gradient_line(&mut svg, &segment, gradients);
+                gradients += 1;
+                index += 1;
+                continue;
+            }
+            // Merge connected segments of the same color into one polyline.
+            let mut points = vec![segment.start.truncate(), segment.end.truncate()];
+            index += 1;
+            while let Some(next) = segments.get(index) {
+                if next.start.truncate() != *points.last().unwrap()
+                    || next.start_color != segment.start_color
+                    || next.end_color != segment.start_color
+                {
+                    break;
+                }
+                points.push(next.end.truncate());
+                index += 1;
+            }
+            let _ = write!(svg, r#"<polyline points=""#);
+            for (i, point) in points.iter().enumerate() {
+                let separator = if i == 0 { "" } else { " " };
+                let _ = write!(svg, "{separator}{},{}", point.x, flip(point.y));
+            }
+            let _ = writeln!(
+                svg,
+                r#"" vector-effect="non-scaling-stroke" {}/>"#,
+                paint("stroke", segment.start_color)
+            );
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+fn write_gradient_line(svg: &mut String, segment: &LineSegment, id: usize) {
+    let (start, end) = (segment.start.truncate(), segment.end.truncate());
+    let _ = writeln!(
+        svg,
+        r#"<linearGradient id="g{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}"><stop offset="0" {}/><stop offset="1" {}/></linearGradient>"#,
+        start.x,
+        flip(start.y),
+        end.x,
+        flip(end.y),
+        paint("stop-color", segment.start_color),
+        paint("stop-color", segment.end_color),
+    );
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" vector-effect="non-scaling-stroke" stroke="url(#g{id})"/>"#,
+        start.x,
+        flip(start.y),
+        end.x,
+        flip(end.y),
+    );
+}
+
+/// Convert a y coordinate from bevy, where y points up, to SVG, where y points down.
+fn flip(y: f32) -> f32 {
+    // Avoids writing "-0".
+    0.0 - y
+}
+
+/// Attributes that paint `property` with `color`, e.g. `stroke="#ff0000"`.
+fn paint(property: &str, color: Color) -> String {
+    let [r, g, b, a] = Srgba::from(color).to_u8_array();
+    let mut attributes = format!(r##"{property}="#{r:02x}{g:02x}{b:02x}""##);
+    if a != u8::MAX {
+        let opacity = match property {
+            "stop-color" => "stop-opacity",
+            "fill" => "fill-opacity",
+            _ => "stroke-opacity",
+        };
+        let _ = write!(attributes, r#" {opacity}="{}""#, a as f32 / 255.0);
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::palettes::css::{BLUE, RED};
+    use bevy_math::{Vec2, Vec3};
+
+    use super::*;
+    use crate::gizmo::{arrow, circle_2d, line_gradient_2d};
+
+    #[test]
+    fn test_svg() {
+        let mut svg = GizmoSvg::new();
+        svg.add_str(&circle_2d(Vec2::ZERO, 10.0, RED)).unwrap();
+        svg.add_str(&line_gradient_2d(Vec2::ZERO, Vec2::ONE, RED, BLUE))
+            .unwrap();
+        svg.add_str(&arrow(Vec3::ZERO, Vec3::ONE, RED)).unwrap();
+        let document = svg.to_svg();
+
+        assert_eq!(svg.skipped(), 1);
+        // The circle is merged into a single polyline.
+        assert_eq!(document.matches("<polyline").count(), 1);
+        assert_eq!(document.matches("<linearGradient").count(), 1);
+        assert!(document.contains(r##"stroke="#ff0000""##));
+    }
+
+    #[test]
+    fn test_recording_with_unknown_variant() {
+        let recording = concat!(
+            r#"(frame:0,timestamp:0.0,level:"DEBUG",target:"game",spans:[],"#,
+            r#"gizmo:"(v:1,gizmo:Spiral(turns:3))")"#,
+            "\n",
+            r#"(frame:0,timestamp:0.0,level:"DEBUG",target:"game",spans:[],"#,
+            r#"gizmo:"(v:1,gizmo:Circle2d(position:(0,0),radius:1,"#,
+            r#"color:LinearRgba((red:1,green:0,blue:0,alpha:1))))")"#,
+        );
+        let mut svg = GizmoSvg::new();
+        svg.add_recording(recording.as_bytes()).unwrap();
+        // The spiral is skipped, and the circle is still drawn.
+        assert_eq!(svg.skipped(), 1);
+        assert_eq!(svg.to_svg().matches("<polyline").count(), 1);
+    }
+}
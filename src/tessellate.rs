@@ -0,0 +1,842 @@
+//! Conversion of gizmo commands into line segments without bevy's renderer.
+//!
+//! The geometry mirrors what [`Gizmos`] draws for each command, so that
+//! gizmo logs can be rendered headlessly.
+//!
+//! [`Gizmos`]: https://docs.rs/bevy/0.15.0/bevy/gizmos/gizmos/struct.Gizmos.html
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy_color::{
+    palettes::basic::{BLUE, GREEN, RED},
+    Color,
+};
+use bevy_math::{Isometry2d, Isometry3d, Mat4, Quat, UVec3, Vec2, Vec3, Vec3Swizzles};
+
+use crate::gizmo::{primitives::cubic_bezier_positions, GizmoCommand};
+
+mod primitives;
+
+const DEFAULT_CIRCLE_RESOLUTION: u32 = 32;
+const DEFAULT_ARC_RESOLUTION: u32 = 8;
+const DEFAULT_CORNER_RADIUS: f32 = 0.1;
+
+/// A straight line with a color at each end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LineSegment {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub start_color: Color,
+    pub end_color: Color,
+}
+
+/// Collects the line segments of gizmo commands.
+#[derive(Default)]
+pub(crate) struct Lines {
+    pub segments: Vec<LineSegment>,
+}
+
+impl GizmoCommand {
+    /// Returns true if the command draws in the xy plane.
+    pub(crate) fn is_2d(&self) -> bool {
+        if let Self::Batch { gizmos } = self {
+            return gizmos.iter().all(GizmoCommand::is_2d);
+        }
+        if let Self::Transformed { transform, gizmo } = self {
+            // The transform must keep the xy plane in place.
+            return gizmo.is_2d()
+                && transform.x_axis.z == 0.
+                && transform.y_axis.z == 0.
+                && transform.w_axis.z == 0.;
+        }
+        matches!(
+            self,
+            Self::Arc2d { .. }
+                | Self::Arrow2d { .. }
+                | Self::Axes2d { .. }
+                | Self::Circle2d { .. }
+                | Self::CubicBezier2d { .. }
+                | Self::Ellipse2d { .. }
+                | Self::Grid2d { .. }
+                | Self::Line2d { .. }
+                | Self::LineGradient2d { .. }
+                | Self::Linestrip2d { .. }
+                | Self::LinestripGradient2d { .. }
+                | Self::Primitive2d { .. }
+                | Self::Ray2d { .. }
+                | Self::RayGradient2d { .. }
+                | Self::Rect2d { .. }
+                | Self::RoundedRect2d { .. }
+        )
+    }
+}
+
+impl Lines {
+    /// Add the segments of a command.
+    pub fn add(&mut self, command: &GizmoCommand) {
+        match *command {
+            GizmoCommand::Arc2d {
+                isometry,
+                arc_angle,
+                radius,
+                color,
+                resolution,
+            } => {
+                self.arc_2d(isometry, arc_angle, radius, resolution, color);
+            }
+            GizmoCommand::Arrow2d {
+                start,
+                end,
+                color,
+                tip_length,
+                double_ended,
+            } => {
+                self.arrow_with(
+                    start.extend(0.),
+                    end.extend(0.),
+                    tip_length,
+                    double_ended,
+                    color,
+                );
+            }
+            GizmoCommand::Axes2d {
+                transform,
+                base_length,
+            } => {
+                let start = transform.transform_point3(Vec3::ZERO).xy();
+                let end_x = transform.transform_point3(base_length * Vec3::X).xy();
+                let end_y = transform.transform_point3(base_length * Vec3::Y).xy();
+                self.arrow(start.extend(0.), end_x.extend(0.), RED.into());
+                self.arrow(start.extend(0.), end_y.extend(0.), GREEN.into());
+            }
+            GizmoCommand::Circle2d {
+                position,
+                radius,
+                color,
+                resolution,
+            } => {
+                self.ellipse_2d(position.into(), Vec2::splat(radius), resolution, color);
+            }
+            GizmoCommand::CubicBezier2d {
+                ref control_points,
+                color,
+            } => {
+                let positions = cubic_bezier_positions(control_points);
+                self.linestrip(positions.into_iter().map(|v| v.extend(0.)), color);
+            }
+            GizmoCommand::Ellipse2d {
+                isometry,
+                half_size,
+                color,
+                resolution,
+            } => {
+                self.ellipse_2d(isometry, half_size, resolution, color);
+            }
+            GizmoCommand::Grid2d {
+                isometry,
+                cell_count,
+                spacing,
+                color,
+                skew,
+                outer_edges: [x, y],
+            } => {
+                self.grid(
+                    isometry_2d_to_3d(isometry),
+                    spacing.extend(0.),
+                    cell_count.extend(0),
+                    skew.extend(0.),
+                    [x, y, true],
+                    color,
+                );
+            }
+            GizmoCommand::Line2d { start, end, color } => {
+                self.line(start.extend(0.), end.extend(0.), color);
+            }
+            GizmoCommand::LineGradient2d {
+                start,
+                end,
+                start_color,
+                end_color,
+            } => {
+                self.line_gradient(start.extend(0.), end.extend(0.), start_color, end_color);
+            }
+            GizmoCommand::Linestrip2d {
+                ref positions,
+                color,
+            } => {
+                self.linestrip(positions.iter().map(|v| v.extend(0.)), color);
+            }
+            GizmoCommand::LinestripGradient2d { ref positions } => {
+                self.linestrip_gradient(positions.iter().map(|&(v, c)| (v.extend(0.), c)));
+            }
+            GizmoCommand::Primitive2d {
+                ref primitive,
+                isometry,
+                color,
+            } => {
+                self.primitive_2d(primitive, isometry, color);
+            }
+            GizmoCommand::Ray2d {
+                start,
+                vector,
+                color,
+            } => {
+                self.line(start.extend(0.), (start + vector).extend(0.), color);
+            }
+            GizmoCommand::RayGradient2d {
+                start,
+                vector,
+                start_color,
+                end_color,
+            } => {
+                self.line_gradient(
+                    start.extend(0.),
+                    (start + vector).extend(0.),
+                    start_color,
+                    end_color,
+                );
+            }
+            GizmoCommand::Rect2d {
+                isometry,
+                size,
+                color,
+            } => {
+                self.rect(isometry_2d_to_3d(isometry), size, color);
+            }
+            GizmoCommand::RoundedRect2d {
+                isometry,
+                size,
+                color,
+                corner_radius,
+                arc_resolution,
+            } => {
+                self.rounded_rect(
+                    isometry_2d_to_3d(isometry),
+                    size,
+                    corner_radius,
+                    arc_resolution,
+                    color,
+                );
+            }
+            GizmoCommand::Arc3d {
+                angle,
+                radius,
+                isometry,
+                color,
+                resolution,
+            } => {
+                let resolution = resolution.unwrap_or_else(|| resolution_from_angle(angle));
+                self.arc_3d(Vec3::X, isometry, angle, radius, resolution, color);
+            }
+            GizmoCommand::Arrow {
+                start,
+                end,
+                color,
+                tip_length,
+                double_ended,
+            } => {
+                self.arrow_with(start, end, tip_length, double_ended, color);
+            }
+            GizmoCommand::Axes {
+                transform,
+                base_length,
+            } => {
+                let start = transform.transform_point3(Vec3::ZERO);
+                self.arrow(
+                    start,
+                    transform.transform_point3(base_length * Vec3::X),
+                    RED.into(),
+                );
+                self.arrow(
+                    start,
+                    transform.transform_point3(base_length * Vec3::Y),
+                    GREEN.into(),
+                );
+                self.arrow(
+                    start,
+                    transform.transform_point3(base_length * Vec3::Z),
+                    BLUE.into(),
+                );
+            }
+            GizmoCommand::Circle {
+                isometry,
+                radius,
+                color,
+                resolution,
+            } => {
+                self.ellipse(isometry, Vec2::splat(radius), resolution, color);
+            }
+            GizmoCommand::CubicBezier {
+                ref control_points,
+                color,
+            } => {
+                self.linestrip(cubic_bezier_positions(control_points), color);
+            }
+            GizmoCommand::Cuboid { transform, color } => {
+                self.cuboid(transform, color);
+            }
+            GizmoCommand::Ellipse {
+                isometry,
+                half_size,
+                color,
+                resolution,
+            } => {
+                self.ellipse(isometry, half_size, resolution, color);
+            }
+            GizmoCommand::Grid {
+                isometry,
+                cell_count,
+                spacing,
+                color,
+                skew,
+                outer_edges: [x, y],
+            } => {
+                self.grid(
+                    isometry,
+                    spacing.extend(0.),
+                    cell_count.extend(0),
+                    skew.extend(0.),
+                    [x, y, true],
+                    color,
+                );
+            }
+            GizmoCommand::Grid3d {
+                isometry,
+                cell_count,
+                spacing,
+                color,
+                skew,
+                outer_edges,
+            } => {
+                self.grid(isometry, spacing, cell_count, skew, outer_edges, color);
+            }
+            GizmoCommand::Line { start, end, color } => {
+                self.line(start, end, color);
+            }
+            GizmoCommand::LineGradient {
+                start,
+                end,
+                start_color,
+                end_color,
+            } => {
+                self.line_gradient(start, end, start_color, end_color);
+            }
+            GizmoCommand::Linestrip {
+                ref positions,
+                color,
+            } => {
+                self.linestrip(positions.iter().copied(), color);
+            }
+            GizmoCommand::LinestripGradient { ref points } => {
+                self.linestrip_gradient(points.iter().copied());
+            }
+            GizmoCommand::LongArc3dBetween {
+                center,
+                from,
+                to,
+                color,
+                resolution,
+            } => {
+                self.arc_3d_between(center, from, to, true, resolution, color);
+            }
+            GizmoCommand::Primitive3d {
+                ref primitive,
+                isometry,
+                color,
+            } => {
+                self.primitive_3d(primitive, isometry, color);
+            }
+            GizmoCommand::Ray {
+                start,
+                vector,
+                color,
+            } => {
+                self.line(start, start + vector, color);
+            }
+            GizmoCommand::RayGradient {
+                start,
+                vector,
+                start_color,
+                end_color,
+            } => {
+                self.line_gradient(start, start + vector, start_color, end_color);
+            }
+            GizmoCommand::Rect {
+                isometry,
+                size,
+                color,
+            } => {
+                self.rect(isometry, size, color);
+            }
+            GizmoCommand::RoundedCuboid {
+                isometry,
+                size,
+                color,
+                edge_radius,
+                arc_resolution,
+            } => {
+                self.rounded_cuboid(isometry, size, edge_radius, arc_resolution, color);
+            }
+            GizmoCommand::RoundedRect {
+                isometry,
+                size,
+                color,
+                corner_radius,
+                arc_resolution,
+            } => {
+                self.rounded_rect(isometry, size, corner_radius, arc_resolution, color);
+            }
+            GizmoCommand::ShortArc3dBetween {
+                center,
+                from,
+                to,
+                color,
+                resolution,
+            } => {
+                self.arc_3d_between(center, from, to, false, resolution, color);
+            }
+            GizmoCommand::Sphere {
+                isometry,
+                radius,
+                color,
+                resolution,
+            } => {
+                self.sphere(isometry, radius, resolution, color);
+            }
+            GizmoCommand::Batch { ref gizmos } => {
+                for gizmo in gizmos {
+                    self.add(gizmo);
+                }
+            }
+            GizmoCommand::Transformed {
+                transform,
+                ref gizmo,
+            } => {
+                let mut local = Lines::default();
+                local.add(gizmo);
+                self.segments
+                    .extend(local.segments.into_iter().map(|segment| LineSegment {
+                        start: transform.transform_point3(segment.start),
+                        end: transform.transform_point3(segment.end),
+                        ..segment
+                    }));
+            }
+            GizmoCommand::Clear { .. } | GizmoCommand::Label { .. } => {}
+        }
+    }
+
+    fn line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.line_gradient(start, end, color, color);
+    }
+
+    fn line_gradient(&mut self, start: Vec3, end: Vec3, start_color: Color, end_color: Color) {
+        // Bevy draws these too, e.g. for the z axis of a 2D grid, but they're invisible.
+        if start == end {
+            return;
+        }
+        self.segments.push(LineSegment {
+            start,
+            end,
+            start_color,
+            end_color,
+        });
+    }
+
+    fn linestrip(&mut self, positions: impl IntoIterator<Item = Vec3>, color: Color) {
+        self.linestrip_gradient(positions.into_iter().map(|v| (v, color)));
+    }
+
+    fn linestrip_gradient(&mut self, points: impl IntoIterator<Item = (Vec3, Color)>) {
+        let mut points = points.into_iter();
+        let Some(mut previous) = points.next() else {
+            return;
+        };
+        for point in points {
+            self.line_gradient(previous.0, point.0, previous.1, point.1);
+            previous = point;
+        }
+    }
+
+    fn arrow(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.arrow_with(start, end, None, false, color);
+    }
+
+    fn arrow_with(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        tip_length: Option<f32>,
+        double_ended: bool,
+        color: Color,
+    ) {
+        self.line(start, end, color);
+        let tip_length = tip_length.unwrap_or_else(|| (end - start).length() / 10.);
+        let Some(pointing) = (end - start).try_normalize() else {
+            return;
+        };
+        self.arrow_tip(end, pointing, tip_length, color);
+        if double_ended {
+            self.arrow_tip(start, -pointing, tip_length, color);
+        }
+    }
+
+    fn arrow_tip(&mut self, position: Vec3, pointing: Vec3, tip_length: f32, color: Color) {
+        let rotation = Quat::from_rotation_arc(Vec3::X, pointing);
+        let tips = [
+            Vec3::new(-1., 1., 0.),
+            Vec3::new(-1., 0., 1.),
+            Vec3::new(-1., -1., 0.),
+            Vec3::new(-1., 0., -1.),
+        ];
+        for tip in tips {
+            self.line(
+                position,
+                rotation * (tip.normalize() * tip_length) + position,
+                color,
+            );
+        }
+    }
+
+    fn arc_2d(
+        &mut self,
+        isometry: Isometry2d,
+        arc_angle: f32,
+        radius: f32,
+        resolution: Option<u32>,
+        color: Color,
+    ) {
+        let resolution = resolution.unwrap_or_else(|| resolution_from_angle(arc_angle));
+        let positions = (0..=resolution)
+            .map(|n| arc_angle * n as f32 / resolution as f32 + FRAC_PI_2)
+            .map(|angle| isometry * (Vec2::from_angle(angle) * radius));
+        self.linestrip(positions.map(|v| v.extend(0.)), color);
+    }
+
+    fn ellipse_2d(
+        &mut self,
+        isometry: Isometry2d,
+        half_size: Vec2,
+        resolution: Option<u32>,
+        color: Color,
+    ) {
+        let resolution = resolution.unwrap_or(DEFAULT_CIRCLE_RESOLUTION);
+        let positions = ellipse_inner(half_size, resolution).map(|v| (isometry * v).extend(0.));
+        self.linestrip(positions, color);
+    }
+
+    fn ellipse(
+        &mut self,
+        isometry: Isometry3d,
+        half_size: Vec2,
+        resolution: Option<u32>,
+        color: Color,
+    ) {
+        let resolution = resolution.unwrap_or(DEFAULT_CIRCLE_RESOLUTION);
+        let positions = ellipse_inner(half_size, resolution).map(|v| isometry * v.extend(0.));
+        self.linestrip(positions, color);
+    }
+
+    fn sphere(&mut self, isometry: Isometry3d, radius: f32, resolution: Option<u32>, color: Color) {
+        for axis in Vec3::AXES {
+            let rotation = Quat::from_rotation_arc(Vec3::Z, axis);
+            self.ellipse(
+                isometry * Isometry3d::from_rotation(rotation),
+                Vec2::splat(radius),
+                resolution,
+                color,
+            );
+        }
+    }
+
+    fn arc_3d(
+        &mut self,
+        start_vertex: Vec3,
+        isometry: Isometry3d,
+        angle: f32,
+        radius: f32,
+        resolution: u32,
+        color: Color,
+    ) {
+        let angle = angle.clamp(-TAU, TAU);
+        let positions = (0..=resolution)
+            .map(|n| angle * n as f32 / resolution as f32)
+            .map(|angle| Quat::from_axis_angle(Vec3::Y, angle) * start_vertex)
+            .map(|v| isometry * (v * radius));
+        self.linestrip(positions, color);
+    }
+
+    fn arc_3d_between(
+        &mut self,
+        center: Vec3,
+        from: Vec3,
+        to: Vec3,
+        long: bool,
+        resolution: Option<u32>,
+        color: Color,
+    ) {
+        let from_axis = (from - center).normalize_or_zero();
+        let to_axis = (to - center).normalize_or_zero();
+        let (up, mut angle) = Quat::from_rotation_arc(from_axis, to_axis).to_axis_angle();
+        if long && angle != 0.0 {
+            angle = angle.signum() * TAU - angle;
+        }
+        let radius = center.distance(from);
+        let rotation = Quat::from_rotation_arc(Vec3::Y, up);
+        let start_vertex = rotation.inverse() * from_axis;
+        let isometry = Isometry3d::new(center, rotation);
+        let resolution = resolution.unwrap_or_else(|| resolution_from_angle(angle));
+        self.arc_3d(start_vertex, isometry, angle, radius, resolution, color);
+    }
+
+    fn cuboid(&mut self, transform: Mat4, color: Color) {
+        let rect = [
+            Vec2::new(-0.5, 0.5),
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.5, -0.5),
+            Vec2::new(-0.5, -0.5),
+        ];
+        let [tlf, trf, brf, blf] = rect.map(|v| transform.transform_point3(v.extend(0.5)));
+        let [tlb, trb, brb, blb] = rect.map(|v| transform.transform_point3(v.extend(-0.5)));
+        self.linestrip([tlf, trf, brf, blf, tlf, tlb, trb, brb, blb, tlb], color);
+        for (front, back) in [(trf, trb), (brf, brb), (blf, blb)] {
+            self.line(front, back, color);
+        }
+    }
+
+    fn rounded_cuboid(
+        &mut self,
+        isometry: Isometry3d,
+        size: Vec3,
+        edge_radius: Option<f32>,
+        arc_resolution: Option<u32>,
+        color: Color,
+    ) {
+        let corner_radius = edge_radius.unwrap_or(size.min_element() * DEFAULT_CORNER_RADIUS);
+        let outer_half_size = size.abs() / 2.0;
+        let inner_half_size = (outer_half_size - Vec3::splat(corner_radius.abs())).max(Vec3::ZERO);
+        let mut edge_radius = (outer_half_size - inner_half_size).min_element();
+        let inner_half_size = outer_half_size - Vec3::splat(edge_radius);
+        edge_radius *= corner_radius.signum();
+
+        if edge_radius == 0.0 {
+            let transform = Mat4::from_scale_rotation_translation(
+                size,
+                isometry.rotation,
+                isometry.translation.into(),
+            );
+            self.cuboid(transform, color);
+            return;
+        }
+
+        let rects = [
+            (
+                Vec3::X,
+                Vec2::new(size.z, size.y),
+                Quat::from_rotation_y(FRAC_PI_2),
+            ),
+            (
+                Vec3::Y,
+                Vec2::new(size.x, size.z),
+                Quat::from_rotation_x(FRAC_PI_2),
+            ),
+            (Vec3::Z, Vec2::new(size.x, size.y), Quat::IDENTITY),
+        ];
+        for (position, size, rotation) in rects {
+            let local_position = position * inner_half_size;
+            for local_position in [local_position, -local_position] {
+                self.rounded_rect(
+                    isometry * Isometry3d::new(local_position, rotation),
+                    size,
+                    Some(edge_radius),
+                    arc_resolution,
+                    color,
+                );
+            }
+        }
+    }
+
+    fn rect(&mut self, isometry: Isometry3d, size: Vec2, color: Color) {
+        let half_size = size / 2.;
+        let [tl, tr, br, bl] = [
+            Vec2::new(-half_size.x, half_size.y),
+            Vec2::new(half_size.x, half_size.y),
+            Vec2::new(half_size.x, -half_size.y),
+            Vec2::new(-half_size.x, -half_size.y),
+        ]
+        .map(|v| isometry * v.extend(0.));
+        self.linestrip([tl, tr, br, bl, tl], color);
+    }
+
+    fn rounded_rect(
+        &mut self,
+        isometry: Isometry3d,
+        size: Vec2,
+        corner_radius: Option<f32>,
+        arc_resolution: Option<u32>,
+        color: Color,
+    ) {
+        let corner_radius = corner_radius.unwrap_or(size.min_element() * DEFAULT_CORNER_RADIUS);
+        let mut outer_half_size = size.abs() / 2.0;
+        let inner_half_size = (outer_half_size - Vec2::splat(corner_radius.abs())).max(Vec2::ZERO);
+        let radius = (outer_half_size - inner_half_size).min_element();
+        let mut inner_half_size = outer_half_size - Vec2::splat(radius);
+        if corner_radius < 0. {
+            std::mem::swap(&mut outer_half_size, &mut inner_half_size);
+        }
+
+        if outer_half_size.x * outer_half_size.y == 0. {
+            self.line(
+                isometry * -outer_half_size.extend(0.),
+                isometry * outer_half_size.extend(0.),
+                color,
+            );
+            return;
+        }
+        if radius == 0. {
+            self.rect(isometry, size, color);
+            return;
+        }
+
+        let (o, i) = (outer_half_size, inner_half_size);
+        let vertices = [
+            Vec3::new(i.x, o.y, 0.),
+            Vec3::new(i.x, i.y, 0.),
+            Vec3::new(o.x, i.y, 0.),
+            Vec3::new(o.x, -i.y, 0.),
+            Vec3::new(i.x, -i.y, 0.),
+            Vec3::new(i.x, -o.y, 0.),
+            Vec3::new(-i.x, -o.y, 0.),
+            Vec3::new(-i.x, -i.y, 0.),
+            Vec3::new(-o.x, -i.y, 0.),
+            Vec3::new(-o.x, i.y, 0.),
+            Vec3::new(-i.x, i.y, 0.),
+            Vec3::new(-i.x, o.y, 0.),
+        ]
+        .map(|v| isometry * v);
+
+        for chunk in vertices.chunks_exact(3) {
+            self.arc_3d_between(
+                chunk[1],
+                chunk[0],
+                chunk[2],
+                false,
+                Some(arc_resolution.unwrap_or(DEFAULT_ARC_RESOLUTION)),
+                color,
+            );
+        }
+
+        let edges = if corner_radius > 0. {
+            [(2, 3), (5, 6), (8, 9), (11, 0)]
+        } else {
+            [(0, 5), (3, 8), (6, 11), (9, 2)]
+        };
+        for (start, end) in edges {
+            self.line(vertices[start], vertices[end], color);
+        }
+    }
+
+    fn grid(
+        &mut self,
+        isometry: Isometry3d,
+        spacing: Vec3,
+        cell_count: UVec3,
+        skew: Vec3,
+        outer_edges: [bool; 3],
+        color: Color,
+    ) {
+        fn or_zero(cond: bool, val: Vec3) -> Vec3 {
+            if cond {
+                val
+            } else {
+                Vec3::ZERO
+            }
+        }
+
+        let skew_tan = Vec3::from(skew.to_array().map(f32::tan));
+        let dx = or_zero(
+            cell_count.x != 0,
+            spacing.x * Vec3::new(1., skew_tan.y, skew_tan.z),
+        );
+        let dy = or_zero(
+            cell_count.y != 0,
+            spacing.y * Vec3::new(skew_tan.x, 1., skew_tan.z),
+        );
+        let dz = or_zero(
+            cell_count.z != 0,
+            spacing.z * Vec3::new(skew_tan.x, skew_tan.y, 1.),
+        );
+
+        let cell_count_half = cell_count.as_vec3() * 0.5;
+        let grid_start = -cell_count_half.x * dx - cell_count_half.y * dy - cell_count_half.z * dz;
+
+        let outer_edges_u32 = UVec3::from(outer_edges.map(|v| v as u32));
+        let line_count = outer_edges_u32 * cell_count.saturating_add(UVec3::ONE)
+            + (UVec3::ONE - outer_edges_u32) * cell_count.saturating_sub(UVec3::ONE);
+
+        let x_start = grid_start + or_zero(!outer_edges[0], dy + dz);
+        let y_start = grid_start + or_zero(!outer_edges[1], dx + dz);
+        let z_start = grid_start + or_zero(!outer_edges[2], dx + dy);
+
+        let axes = [
+            (
+                dx,
+                dy,
+                dz,
+                line_count.y,
+                line_count.z,
+                cell_count.x,
+                x_start,
+            ),
+            (
+                dy,
+                dz,
+                dx,
+                line_count.z,
+                line_count.x,
+                cell_count.y,
+                y_start,
+            ),
+            (
+                dz,
+                dx,
+                dy,
+                line_count.x,
+                line_count.y,
+                cell_count.z,
+                z_start,
+            ),
+        ];
+        for (delta_a, delta_b, delta_c, count_b, count_c, cells, start) in axes {
+            let dline = delta_a * cells as f32;
+            for b in 0..count_b {
+                for c in 0..count_c {
+                    let line_start = start + b as f32 * delta_b + c as f32 * delta_c;
+                    self.line(
+                        isometry * line_start,
+                        isometry * (line_start + dline),
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn ellipse_inner(half_size: Vec2, resolution: u32) -> impl Iterator<Item = Vec2> {
+    (0..resolution + 1).map(move |i| {
+        let angle = i as f32 * TAU / resolution as f32;
+        let (x, y) = angle.sin_cos();
+        Vec2::new(x, y) * half_size
+    })
+}
+
+fn resolution_from_angle(angle: f32) -> u32 {
+    ((angle.abs() / TAU) * DEFAULT_CIRCLE_RESOLUTION as f32).ceil() as u32
+}
+
+fn isometry_2d_to_3d(isometry: Isometry2d) -> Isometry3d {
+    Isometry3d::new(
+        isometry.translation.extend(0.0),
+        Quat::from_rotation_z(isometry.rotation.as_radians()),
+    )
+}
@@ -0,0 +1,351 @@
+//! Line segments of [`Shape2d`] and [`Shape3d`], mirroring bevy's
+//! [`primitives`] gizmos.
+//!
+//! [`primitives`]: https://docs.rs/bevy/0.15.0/bevy/gizmos/primitives/index.html
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy_color::Color;
+use bevy_math::{Dir2, Isometry2d, Isometry3d, Quat, Rot2, UVec3, Vec2, Vec3};
+
+use super::{ellipse_inner, Lines};
+use crate::gizmo::{Shape2d, Shape3d};
+
+const MIN_LINE_LEN: f32 = 50.0;
+const HALF_MIN_LINE_LEN: f32 = 25.0;
+const INFINITE_LEN_2D: f32 = 100_000.0;
+const INFINITE_LEN_3D: f32 = 10_000.0;
+const DEFAULT_RESOLUTION_3D: u32 = 5;
+
+impl Lines {
+    pub(super) fn primitive_2d(&mut self, primitive: &Shape2d, isometry: Isometry2d, color: Color) {
+        match *primitive {
+            Shape2d::Annulus(annulus) => {
+                for circle in [annulus.outer_circle, annulus.inner_circle] {
+                    self.ellipse_2d(isometry, Vec2::splat(circle.radius), None, color);
+                }
+            }
+            Shape2d::Arc2d(arc) => {
+                let start = isometry * Isometry2d::from_rotation(Rot2::radians(-arc.half_angle));
+                self.arc_2d(start, arc.half_angle * 2.0, arc.radius, None, color);
+            }
+            Shape2d::BoxedPolygon(ref polygon) => {
+                let vertices = &polygon.vertices;
+                let closing_point = (vertices.last() != vertices.first())
+                    .then(|| vertices.first().copied())
+                    .flatten();
+                let positions = vertices.iter().copied().chain(closing_point);
+                self.linestrip_2d(positions.map(|v| isometry * v), color);
+            }
+            Shape2d::BoxedPolyline2d(ref polyline) => {
+                self.linestrip_2d(polyline.vertices.iter().map(|&v| isometry * v), color);
+            }
+            Shape2d::Capsule2d(capsule) => {
+                let scaling = Vec2::new(capsule.radius, capsule.half_length);
+                let [top_left, top_right, bottom_left, bottom_right, top_center, bottom_center] = [
+                    Vec2::new(-1.0, 1.0),
+                    Vec2::new(1.0, 1.0),
+                    Vec2::new(-1.0, -1.0),
+                    Vec2::new(1.0, -1.0),
+                    Vec2::new(0.0, 1.0),
+                    Vec2::new(0.0, -1.0),
+                ]
+                .map(|v| isometry * (v * scaling));
+                self.line_2d(bottom_left, top_left, color);
+                self.line_2d(bottom_right, top_right, color);
+                // Bevy starts both arcs relative to the rotation of the isometry.
+                let start_angle_top = isometry.rotation.as_radians() - FRAC_PI_2;
+                let start_angle_bottom = isometry.rotation.as_radians() + FRAC_PI_2;
+                self.arc_2d(
+                    Isometry2d::new(top_center, Rot2::radians(start_angle_top)),
+                    PI,
+                    capsule.radius,
+                    None,
+                    color,
+                );
+                self.arc_2d(
+                    Isometry2d::new(bottom_center, Rot2::radians(start_angle_bottom)),
+                    PI,
+                    capsule.radius,
+                    None,
+                    color,
+                );
+            }
+            Shape2d::Circle(circle) => {
+                self.ellipse_2d(isometry, Vec2::splat(circle.radius), None, color);
+            }
+            Shape2d::CircularSector(sector) => {
+                let arc = sector.arc;
+                let start = isometry * Isometry2d::from_rotation(Rot2::radians(-arc.half_angle));
+                let end = isometry * Isometry2d::from_rotation(Rot2::radians(arc.half_angle));
+                self.arc_2d(start, arc.half_angle * 2.0, arc.radius, None, color);
+                let end_position = arc.radius * Vec2::Y;
+                self.line_2d(isometry * Vec2::ZERO, start * end_position, color);
+                self.line_2d(isometry * Vec2::ZERO, end * end_position, color);
+            }
+            Shape2d::CircularSegment(segment) => {
+                let arc = segment.arc;
+                let start = isometry * Isometry2d::from_rotation(Rot2::radians(-arc.half_angle));
+                let end = isometry * Isometry2d::from_rotation(Rot2::radians(arc.half_angle));
+                self.arc_2d(start, arc.half_angle * 2.0, arc.radius, None, color);
+                let position = arc.radius * Vec2::Y;
+                self.line_2d(start * position, end * position, color);
+            }
+            Shape2d::Ellipse(ellipse) => {
+                self.ellipse_2d(isometry, ellipse.half_size, None, color);
+            }
+            Shape2d::Line2d(line) => {
+                self.infinite_line_2d(line.direction, isometry, color);
+            }
+            Shape2d::Plane2d(plane) => {
+                let normal = plane.normal;
+                let normal_isometry =
+                    Isometry2d::new(isometry * (HALF_MIN_LINE_LEN * normal), isometry.rotation);
+                self.arrow_2d(
+                    normal_isometry * (-normal * HALF_MIN_LINE_LEN),
+                    normal_isometry * (normal * HALF_MIN_LINE_LEN),
+                    color,
+                );
+                let direction = Dir2::new_unchecked(-normal.perp());
+                self.infinite_line_2d(direction, isometry, color);
+                self.arrow_2d(
+                    isometry * Vec2::ZERO,
+                    isometry * (MIN_LINE_LEN * direction),
+                    color,
+                );
+            }
+            Shape2d::Rectangle(rectangle) => {
+                let [a, b, c, d] = [(1.0, 1.0), (1.0, -1.0), (-1.0, -1.0), (-1.0, 1.0)]
+                    .map(|(x, y)| isometry * (Vec2::new(x, y) * rectangle.half_size));
+                self.linestrip_2d([a, b, c, d, a], color);
+            }
+            Shape2d::RegularPolygon(polygon) => {
+                let positions =
+                    ellipse_inner(Vec2::splat(polygon.circumcircle.radius), polygon.sides);
+                self.linestrip_2d(positions.map(|v| isometry * v), color);
+            }
+            Shape2d::Rhombus(rhombus) => {
+                let [a, b, c, d] = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)]
+                    .map(|(x, y)| isometry * (Vec2::new(x, y) * rhombus.half_diagonals));
+                self.linestrip_2d([a, b, c, d, a], color);
+            }
+            Shape2d::Segment2d(segment) => {
+                let direction = segment.direction * segment.half_length;
+                self.line_2d(isometry * -direction, isometry * direction, color);
+            }
+            Shape2d::Triangle2d(triangle) => {
+                let [a, b, c] = triangle.vertices.map(|v| isometry * v);
+                self.linestrip_2d([a, b, c, a], color);
+            }
+        }
+    }
+
+    pub(super) fn primitive_3d(&mut self, primitive: &Shape3d, isometry: Isometry3d, color: Color) {
+        match *primitive {
+            Shape3d::BoxedPolyline3d(ref polyline) => {
+                self.linestrip(polyline.vertices.iter().map(|&v| isometry * v), color);
+            }
+            Shape3d::Capsule3d(capsule) => {
+                let (radius, half_length) = (capsule.radius, capsule.half_length);
+                let [upper_apex, lower_apex] =
+                    [-1.0, 1.0].map(|sign| isometry * (Vec3::Y * sign * (half_length + radius)));
+                let [upper_center, lower_center] =
+                    [-1.0, 1.0].map(|sign| isometry * (Vec3::Y * sign * half_length));
+                let [upper_points, lower_points] = [-1.0, 1.0].map(|sign| {
+                    circle_points_3d(radius, DEFAULT_RESOLUTION_3D, Vec3::Y * sign * half_length)
+                        .map(|v| isometry * v)
+                        .collect::<Vec<_>>()
+                });
+                for &start in upper_points.iter().skip(1) {
+                    self.arc_3d_between(upper_center, start, upper_apex, false, None, color);
+                }
+                for &start in lower_points.iter().skip(1) {
+                    self.arc_3d_between(lower_center, start, lower_apex, false, None, color);
+                }
+                let circle_rotation = isometry.rotation * Quat::from_rotation_x(FRAC_PI_2);
+                for center in [upper_center, lower_center] {
+                    self.ellipse(
+                        Isometry3d::new(center, circle_rotation),
+                        Vec2::splat(radius),
+                        None,
+                        color,
+                    );
+                }
+                for (start, end) in upper_points.into_iter().zip(lower_points).skip(1) {
+                    self.line(start, end, color);
+                }
+            }
+            Shape3d::Cone(cone) => {
+                let half_height = cone.height * 0.5;
+                let apex = isometry * (Vec3::Y * half_height);
+                let base = circle_points_3d(
+                    cone.radius,
+                    DEFAULT_RESOLUTION_3D,
+                    half_height * Vec3::NEG_Y,
+                )
+                .map(|v| isometry * v)
+                .collect::<Vec<_>>();
+                for &start in base.iter().skip(1) {
+                    self.line(start, apex, color);
+                }
+                self.linestrip(base, color);
+            }
+            Shape3d::ConicalFrustum(frustum) => {
+                self.conical_frustum(
+                    frustum.radius_top,
+                    frustum.radius_bottom,
+                    frustum.height,
+                    isometry,
+                    color,
+                );
+            }
+            Shape3d::Cuboid(cuboid) => {
+                let vertices @ [a, b, c, d, e, f, g, h] = [
+                    [1.0, 1.0, 1.0],
+                    [-1.0, 1.0, 1.0],
+                    [-1.0, -1.0, 1.0],
+                    [1.0, -1.0, 1.0],
+                    [1.0, 1.0, -1.0],
+                    [-1.0, 1.0, -1.0],
+                    [-1.0, -1.0, -1.0],
+                    [1.0, -1.0, -1.0],
+                ]
+                .map(|v| isometry * (Vec3::from(v) * cuboid.half_size));
+                let upper = [(a, b), (b, c), (c, d), (d, a)];
+                let lower = [(e, f), (f, g), (g, h), (h, e)];
+                let connections = vertices.into_iter().zip(vertices.into_iter().skip(4));
+                for (start, end) in upper.into_iter().chain(lower).chain(connections) {
+                    self.line(start, end, color);
+                }
+            }
+            Shape3d::Cylinder(cylinder) => {
+                self.conical_frustum(
+                    cylinder.radius,
+                    cylinder.radius,
+                    cylinder.half_height * 2.0,
+                    isometry,
+                    color,
+                );
+            }
+            Shape3d::Line3d(line) => {
+                let direction = line.direction.as_vec3();
+                self.arrow(isometry * Vec3::ZERO, isometry * direction, color);
+                self.line(
+                    isometry * (direction * INFINITE_LEN_3D),
+                    isometry * (direction * -INFINITE_LEN_3D),
+                    color,
+                );
+            }
+            Shape3d::Plane3d(plane) => {
+                let normal = plane.normal.as_vec3();
+                self.arrow(isometry * Vec3::ZERO, isometry * normal, color);
+                let rotation = Quat::from_rotation_arc(Vec3::Z, normal);
+                self.grid(
+                    Isometry3d::new(isometry.translation, isometry.rotation * rotation),
+                    Vec3::new(1.0, 1.0, 0.0),
+                    UVec3::new(3, 3, 0),
+                    Vec3::ZERO,
+                    [false, false, true],
+                    color,
+                );
+            }
+            Shape3d::Segment3d(segment) => {
+                // Bevy 0.15 ignores the half length of the segment.
+                let direction = segment.direction.as_vec3();
+                self.line(isometry * direction, isometry * -direction, color);
+            }
+            Shape3d::Sphere(sphere) => {
+                self.sphere(isometry, sphere.radius, None, color);
+            }
+            Shape3d::Tetrahedron(tetrahedron) => {
+                let [a, b, c, d] = tetrahedron.vertices.map(|v| isometry * v);
+                for (start, end) in [(a, b), (a, c), (a, d), (b, c), (b, d), (c, d)] {
+                    self.line(start, end, color);
+                }
+            }
+            Shape3d::Torus(torus) => {
+                let (major, minor) = (torus.major_radius, torus.minor_radius);
+                let [inner, outer, top, bottom] = [
+                    (major - minor, 0.0),
+                    (major + minor, 0.0),
+                    (major, minor),
+                    (major, -minor),
+                ]
+                .map(|(radius, height)| {
+                    circle_points_3d(radius, DEFAULT_RESOLUTION_3D, height * Vec3::Y)
+                        .map(|v| isometry * v)
+                        .collect::<Vec<_>>()
+                });
+                for points in [&inner, &outer, &top, &bottom] {
+                    self.linestrip(points.iter().copied(), color);
+                }
+                let rings = inner.into_iter().zip(top).zip(outer).zip(bottom);
+                for (((inner, top), outer), bottom) in rings {
+                    let center = (inner + top + outer + bottom) * 0.25;
+                    for (from, to) in [(inner, top), (top, outer), (outer, bottom), (bottom, inner)]
+                    {
+                        self.arc_3d_between(
+                            center,
+                            from,
+                            to,
+                            false,
+                            Some(DEFAULT_RESOLUTION_3D),
+                            color,
+                        );
+                    }
+                }
+            }
+            Shape3d::Triangle3d(triangle) => {
+                let [a, b, c] = triangle.vertices.map(|v| isometry * v);
+                self.linestrip([a, b, c, a], color);
+            }
+        }
+    }
+
+    fn line_2d(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.line(start.extend(0.), end.extend(0.), color);
+    }
+
+    fn arrow_2d(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.arrow(start.extend(0.), end.extend(0.), color);
+    }
+
+    fn linestrip_2d(&mut self, positions: impl IntoIterator<Item = Vec2>, color: Color) {
+        self.linestrip(positions.into_iter().map(|v| v.extend(0.)), color);
+    }
+
+    fn infinite_line_2d(&mut self, direction: Dir2, isometry: Isometry2d, color: Color) {
+        self.line_2d(
+            isometry * (direction * INFINITE_LEN_2D),
+            isometry * (direction * -INFINITE_LEN_2D),
+            color,
+        );
+    }
+
+    fn conical_frustum(
+        &mut self,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        isometry: Isometry3d,
+        color: Color,
+    ) {
+        let half_height = height * 0.5;
+        let [upper_points, lower_points] =
+            [(-1.0, radius_bottom), (1.0, radius_top)].map(|(sign, radius)| {
+                circle_points_3d(radius, DEFAULT_RESOLUTION_3D, Vec3::Y * sign * half_height)
+                    .map(|v| isometry * v)
+                    .collect::<Vec<_>>()
+            });
+        self.linestrip(upper_points.iter().copied(), color);
+        self.linestrip(lower_points.iter().copied(), color);
+        for (start, end) in upper_points.into_iter().zip(lower_points).skip(1) {
+            self.line(start, end, color);
+        }
+    }
+}
+
+/// A closed circle in the xz plane, as used by bevy's 3D primitive gizmos.
+fn circle_points_3d(radius: f32, resolution: u32, center: Vec3) -> impl Iterator<Item = Vec3> {
+    ellipse_inner(Vec2::splat(radius), resolution).map(move |v| Vec3::new(v.x, 0.0, v.y) + center)
+}
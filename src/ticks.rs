@@ -0,0 +1,115 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use bevy_app::{App, FixedFirst, FixedLast, FixedPostUpdate, FixedPreUpdate, FixedUpdate};
+use bevy_ecs::{
+    schedule::{ExecutorKind, ScheduleLabel},
+    system::Resource,
+    world::World,
+};
+
+use crate::retained::PlacedGizmo;
+
+/// Bevy resource that counts the ticks of the fixed timestep, so that
+/// [`GizmoLayer`](crate::GizmoLayer) can attribute gizmos to the tick they
+/// were logged in.
+#[derive(Resource, Clone, Default)]
+pub struct FixedTicks(Arc<TickState>);
+
+#[derive(Default)]
+struct TickState {
+    completed: AtomicU64,
+}
+
+thread_local! {
+    /// The [`TickState`] whose tick is being run on this thread, by address.
+    ///
+    /// Ticks are scoped to the thread running the fixed timestep schedules, so
+    /// that gizmos logged from other threads meanwhile aren't attributed to them.
+    static RUNNING: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+impl FixedTicks {
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+
+    /// The tick being run, numbered from 0, if the fixed timestep is running
+    /// on this thread.
+    pub(crate) fn current(&self) -> Option<u64> {
+        (RUNNING.get() == Some(self.id())).then(|| self.0.completed.load(Ordering::Acquire))
+    }
+
+    /// The last tick that completed, if any.
+    pub(crate) fn last_completed(&self) -> Option<u64> {
+        self.0.completed.load(Ordering::Acquire).checked_sub(1)
+    }
+}
+
+/// Gizmos attributed to ticks, kept until a later tick completes.
+#[derive(Default)]
+pub struct TickGizmos(Vec<(u64, PlacedGizmo)>);
+
+impl TickGizmos {
+    /// Forget the gizmos of ticks before `last_tick`.
+    pub(crate) fn prune(&mut self, last_tick: Option<u64>) {
+        self.0.retain(|(tick, _)| Some(*tick) >= last_tick);
+    }
+
+    /// Keep `gizmo` unless its tick is older than `last_tick`.
+    pub(crate) fn push(&mut self, tick: u64, gizmo: PlacedGizmo, last_tick: Option<u64>) {
+        if Some(tick) >= last_tick {
+            self.0.push((tick, gizmo));
+        }
+    }
+
+    /// The gizmos of `last_tick`. Gizmos of the tick being run are kept
+    /// until it completes.
+    pub(crate) fn of_tick(&self, last_tick: Option<u64>) -> impl Iterator<Item = &PlacedGizmo> {
+        self.0
+            .iter()
+            .filter(move |(tick, _)| Some(*tick) == last_tick)
+            .map(|(_, gizmo)| gizmo)
+    }
+}
+
+/// Setup `app` to count ticks and return the counter.
+pub(crate) fn init_ticks(app: &mut App) -> FixedTicks {
+    app.init_resource::<FixedTicks>()
+        .add_systems(FixedFirst, start_tick)
+        .add_systems(FixedLast, complete_tick);
+    app.world().resource::<FixedTicks>().clone()
+}
+
+/// Run the fixed timestep schedules between [`FixedFirst`] and [`FixedLast`]
+/// on the thread that runs the fixed timestep, where ticks are counted.
+pub(crate) fn run_fixed_schedules_on_tick_thread(app: &mut App) {
+    for schedule in [
+        FixedPreUpdate.intern(),
+        FixedUpdate.intern(),
+        FixedPostUpdate.intern(),
+    ] {
+        app.edit_schedule(schedule, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+}
+
+// Exclusive systems run on the thread that runs the schedule.
+fn start_tick(world: &mut World) {
+    RUNNING.set(Some(world.resource::<FixedTicks>().id()));
+}
+
+fn complete_tick(world: &mut World) {
+    world
+        .resource::<FixedTicks>()
+        .0
+        .completed
+        .fetch_add(1, Ordering::AcqRel);
+    RUNNING.set(None);
+}
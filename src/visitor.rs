@@ -0,0 +1,144 @@
+use std::fmt;
+
+use tracing::{
+    field::{Field, Visit},
+    Event,
+};
+
+use crate::gizmo::{
+    typed,
+    wire::{self, DecodeError},
+    GizmoCommand, GizmoKey, GizmoLifetime, GizmoSpace,
+};
+
+/// A gizmo command along with the options it was logged with.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "bevy"), allow(dead_code))]
+pub(crate) struct LoggedGizmo {
+    pub(crate) command: GizmoCommand,
+    pub(crate) lifetime: Option<GizmoLifetime>,
+    pub(crate) key: Option<GizmoKey>,
+    /// The `gizmo.group` field, naming the config group to draw the gizmo in.
+    pub(crate) group: Option<String>,
+    /// The `gizmo.entity` field, the bits of the entity the gizmo is attached to.
+    pub(crate) entity: Option<u64>,
+    /// The message of the event, which can be drawn as a label next to the gizmo.
+    pub(crate) message: Option<String>,
+    /// The `gizmo.space` field, the coordinate space of the command.
+    pub(crate) space: GizmoSpace,
+}
+
+/// Why the gizmo field of an event couldn't be turned into a gizmo.
+#[derive(Debug)]
+pub(crate) enum GizmoFieldError {
+    /// The field is a string that isn't a valid gizmo.
+    Decode(DecodeError),
+    /// The field is neither a string nor a [`GizmoValue`](typed::GizmoValue).
+    /// Holds the debug representation of the value.
+    NotAGizmo(String),
+}
+
+impl fmt::Display for GizmoFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GizmoFieldError::Decode(err) => err.fmt(f),
+            GizmoFieldError::NotAGizmo(value) => write!(
+                f,
+                "gizmo field `{value}` is neither a gizmo string nor a GizmoValue"
+            ),
+        }
+    }
+}
+
+/// Visitor that extracts the gizmo fields of an event into a LoggedGizmo.
+#[derive(Default)]
+struct GizmoVisitor {
+    command: Option<Result<GizmoCommand, GizmoFieldError>>,
+    lifetime: Option<GizmoLifetime>,
+    key: Option<GizmoKey>,
+    group: Option<String>,
+    entity: Option<u64>,
+    message: Option<String>,
+    space: GizmoSpace,
+}
+
+impl Visit for GizmoVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            // Only typed gizmo values are supported as debug fields.
+            "gizmo" => {
+                self.command = Some(match typed::capture(value) {
+                    Some(gizmo_command) => Ok(gizmo_command),
+                    None => Err(GizmoFieldError::NotAGizmo(format!("{value:?}"))),
+                });
+            }
+            "message" => self.message = Some(format!("{value:?}")),
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        match field.name() {
+            "gizmo" => self.record_debug(field, &value),
+            "gizmo.ttl_secs" => self.lifetime = Some(GizmoLifetime::Seconds(value as f32)),
+            _ => {}
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        match field.name() {
+            "gizmo" => self.record_debug(field, &value),
+            "gizmo.key" => self.key = Some(GizmoKey::Num(value as u64)),
+            _ => self.record_u64(field, value.max(0) as u64),
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "gizmo" => self.record_debug(field, &value),
+            "gizmo.ttl_secs" => self.lifetime = Some(GizmoLifetime::Seconds(value as f32)),
+            "gizmo.ttl_frames" => {
+                let frames = value.try_into().unwrap_or(u32::MAX);
+                self.lifetime = Some(GizmoLifetime::Frames(frames));
+            }
+            "gizmo.key" => self.key = Some(GizmoKey::Num(value)),
+            "gizmo.entity" => self.entity = Some(value),
+            _ => {}
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "gizmo" => {
+                self.command = Some(wire::decode(value).map_err(GizmoFieldError::Decode));
+            }
+            "gizmo.key" => self.key = Some(GizmoKey::from(value)),
+            "gizmo.group" => self.group = Some(value.to_owned()),
+            // Unknown spaces are ignored like unknown fields.
+            "gizmo.space" => self.space = GizmoSpace::parse(value).unwrap_or_default(),
+            "message" => self.message = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+}
+
+/// Convenience function for creating a one-off visitor and using it on one event.
+///
+/// Returns `None` if the event has no gizmo field, or an error if the gizmo can't be decoded.
+pub(crate) fn extract_gizmo_command(
+    event: &Event<'_>,
+) -> Option<Result<LoggedGizmo, GizmoFieldError>> {
+    // Checked before visiting, so that the message of other events isn't copied.
+    event.metadata().fields().field("gizmo")?;
+    let mut visitor = GizmoVisitor::default();
+    event.record(&mut visitor);
+    Some(visitor.command?.map(|command| LoggedGizmo {
+        command,
+        lifetime: visitor.lifetime,
+        key: visitor.key,
+        group: visitor.group,
+        entity: visitor.entity,
+        message: visitor.message,
+        space: visitor.space,
+    }))
+}